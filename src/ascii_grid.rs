@@ -1,15 +1,92 @@
-use std::fmt::{Debug, Formatter, Write};
-use std::ops::Deref;
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::io;
+use std::iter::StepBy;
+use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::str::FromStr;
+use std::slice::Iter;
 use anyhow::{anyhow, bail};
 use glam::IVec2;
 
-pub struct AsciiGrid {
-    contents: Vec<u8>,
+mod expand;
+mod flood;
+mod parse;
+mod pattern;
+mod pipes;
+mod ray;
+mod runs;
+mod sparse;
+mod summed_area;
+mod view;
+
+pub use expand::Expansion;
+pub use flood::{Components, Connectivity, Region};
+pub use parse::{BlankLines, GridParser, ParseGridError, ReadGridError};
+pub use pipes::{Connections, PipeTiles};
+pub use ray::Ray;
+pub use runs::{Runs, Span};
+pub use sparse::SparseGrid;
+pub use summed_area::SummedArea;
+pub use view::{GridView, Symmetry};
+
+/// A tile which can be drawn as a single character when debug printing a
+/// [`Grid`].
+pub trait GridTile {
+    fn to_char(&self) -> char;
+}
+
+impl GridTile for u8 {
+    fn to_char(&self) -> char {
+        *self as char
+    }
+}
+
+impl GridTile for char {
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl GridTile for bool {
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    contents: Vec<T>,
     width: usize,
     height: usize,
 }
 
-impl AsciiGrid {
+pub type AsciiGrid = Grid<u8>;
+
+pub type Column<'a, T> = StepBy<Iter<'a, T>>;
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> where T: Clone {
+        Grid {
+            contents: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(IVec2) -> T) -> Grid<T> {
+        let mut contents = Vec::with_capacity(width * height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                contents.push(f(IVec2::new(x, y)));
+            }
+        }
+
+        Grid {
+            contents,
+            width,
+            height,
+        }
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -18,28 +95,190 @@ impl AsciiGrid {
         self.height
     }
 
-    pub fn get(&self, at: IVec2) -> Option<u8> {
-        if at.x >= 0 && (at.x as usize) < self.width && at.y >= 0 && (at.y as usize) < self.height {
-            Some(self.contents[at.x as usize + at.y as usize * self.width])
+    pub fn size(&self) -> IVec2 {
+        IVec2::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn contains(&self, at: IVec2) -> bool {
+        at.x >= 0 && (at.x as usize) < self.width && at.y >= 0 && (at.y as usize) < self.height
+    }
+
+    pub fn to_offset(&self, at: IVec2) -> Option<usize> {
+        if self.contains(at) {
+            Some(at.x as usize + at.y as usize * self.width)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_offset(&self, offset: usize) -> Option<IVec2> {
+        if offset < self.contents.len() {
+            Some(IVec2::new((offset % self.width) as i32, (offset / self.width) as i32))
         } else {
             None
         }
     }
+
+    pub fn get(&self, at: IVec2) -> Option<T> where T: Copy {
+        self.get_ref(at).copied()
+    }
+
+    pub fn get_ref(&self, at: IVec2) -> Option<&T> {
+        self.to_offset(at).map(|i| &self.contents[i])
+    }
+
+    pub fn get_mut(&mut self, at: IVec2) -> Option<&mut T> {
+        self.to_offset(at).map(|i| &mut self.contents[i])
+    }
+
+    /// Replace the cell at `at`, returning the previous value, or `None` if
+    /// `at` is outside the grid.
+    pub fn set(&mut self, at: IVec2, value: T) -> Option<T> {
+        self.get_mut(at).map(|b| std::mem::replace(b, value))
+    }
+
+    /// Swap the cells at `a` and `b`, returning `false` (and leaving the grid
+    /// untouched) if either is outside the grid.
+    pub fn swap(&mut self, a: IVec2, b: IVec2) -> bool {
+        let (Some(a), Some(b)) = (self.to_offset(a), self.to_offset(b)) else {
+            return false;
+        };
+
+        self.contents.swap(a, b);
+        true
+    }
+
+    /// Every row, including empty ones when the width is zero.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        let width = self.width;
+        (0..self.height).map(move |y| &self.contents[y * width..(y + 1) * width])
+    }
+
+    /// Every column, including empty ones when the height is zero.
+    pub fn columns(&self) -> impl DoubleEndedIterator<Item = Column<'_, T>> + ExactSizeIterator {
+        (0..self.width).map(|x| self.contents.get(x..).unwrap_or(&[]).iter().step_by(self.width))
+    }
+
+    /// Every position in the grid, in row-major order.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = IVec2> + ExactSizeIterator {
+        let width = self.width;
+        (0..self.contents.len()).map(move |i| IVec2::new((i % width) as i32, (i / width) as i32))
+    }
+
+    pub fn enumerate(&self) -> impl DoubleEndedIterator<Item = (IVec2, T)> + ExactSizeIterator + '_ where T: Copy {
+        self.positions().zip(self.contents.iter().copied())
+    }
+
+    pub fn find_all(&self, value: T) -> impl DoubleEndedIterator<Item = IVec2> + '_ where T: PartialEq {
+        self.positions()
+            .zip(self.contents.iter())
+            .filter_map(move |(p, v)| (*v == value).then_some(p))
+    }
+
+    pub fn position_of(&self, value: T) -> Option<IVec2> where T: PartialEq {
+        self.contents.iter()
+            .position(|v| *v == value)
+            .and_then(|i| self.from_offset(i))
+    }
+
+    /// The in-bounds orthogonal neighbours of `at`.
+    pub fn neighbours4(&self, at: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        Direction::cardinal()
+            .map(move |d| at + d.delta())
+            .filter(|&p| self.contains(p))
+    }
+
+    /// The in-bounds orthogonal and diagonal neighbours of `at`.
+    pub fn neighbours8(&self, at: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        Direction::all()
+            .map(move |d| at + d.delta())
+            .filter(|&p| self.contains(p))
+    }
+
+    pub fn view(&self) -> GridView<'_, T> {
+        GridView::new(self)
+    }
+
+    pub fn transpose(&self) -> GridView<'_, T> {
+        self.view().transpose()
+    }
+
+    pub fn flip_horizontal(&self) -> GridView<'_, T> {
+        self.view().flip_horizontal()
+    }
+
+    pub fn flip_vertical(&self) -> GridView<'_, T> {
+        self.view().flip_vertical()
+    }
+
+    pub fn rotate_cw(&self) -> GridView<'_, T> {
+        self.view().rotate_cw()
+    }
+
+    pub fn rotate_ccw(&self) -> GridView<'_, T> {
+        self.view().rotate_ccw()
+    }
+
+    pub fn crop(&self, min: IVec2, max: IVec2) -> GridView<'_, T> {
+        self.view().crop(min, max)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            contents: self.contents.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn try_map<U, E>(&self, f: impl FnMut(&T) -> Result<U, E>) -> Result<Grid<U>, E> {
+        Ok(Grid {
+            contents: self.contents.iter().map(f).collect::<Result<_, _>>()?,
+            width: self.width,
+            height: self.height,
+        })
+    }
+}
+
+impl<T> Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: IVec2) -> &Self::Output {
+        let Some(offset) = self.to_offset(index) else {
+            panic!("position {index} out of bounds for {}x{} grid", self.width, self.height);
+        };
+        &self.contents[offset]
+    }
+}
+
+impl<T> IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, index: IVec2) -> &mut Self::Output {
+        let Some(offset) = self.to_offset(index) else {
+            panic!("position {index} out of bounds for {}x{} grid", self.width, self.height);
+        };
+        &mut self.contents[offset]
+    }
 }
 
-impl Deref for AsciiGrid {
-    type Target = [u8];
+impl<T> Deref for Grid<T> {
+    type Target = [T];
 
     fn deref(&self) -> &Self::Target {
         &self.contents
     }
 }
 
-impl Debug for AsciiGrid {
+impl<T> DerefMut for Grid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.contents
+    }
+}
+
+impl<T: GridTile> Debug for Grid<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height() as i32 {
-            for x in 0..self.width() as i32 {
-                f.write_char(self.get(IVec2::new(x, y)).unwrap() as char)?;
+        for row in self.rows() {
+            for tile in row {
+                f.write_char(tile.to_char())?;
             }
             f.write_char('\n')?;
         }
@@ -48,39 +287,34 @@ impl Debug for AsciiGrid {
     }
 }
 
-impl TryFrom<&str> for AsciiGrid {
-    type Error = anyhow::Error;
+impl AsciiGrid {
+    /// Write the grid's bytes exactly, one row per line. Reading the output
+    /// back with [`GridParser::parse_reader`] (with trimming disabled if the
+    /// grid has whitespace at the edges) reproduces the grid.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        for row in self.rows() {
+            out.write_all(row)?;
+            out.write_all(b"\n")?;
+        }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut lines = value.lines()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty());
-        let first_line = lines.next()
-            .ok_or_else(|| anyhow!("empty string"))?;
-        let width = first_line.len();
-        let mut height = 1;
-        let mut contents = Vec::with_capacity(width * lines.size_hint().0);
-        contents.extend_from_slice(first_line.as_bytes());
-
-        for line in lines {
-            if line.len() != width {
-                bail!("mismatched line length");
-            }
+        Ok(())
+    }
+}
 
-            contents.extend_from_slice(line.as_bytes());
-            height += 1;
+/// Rows are written as UTF-8 text, replacing any invalid sequences; use
+/// [`AsciiGrid::write_to`] for an exact copy of arbitrary bytes.
+impl Display for AsciiGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            f.write_str(&String::from_utf8_lossy(row))?;
+            f.write_char('\n')?;
         }
 
-        contents.shrink_to_fit();
-        Ok(AsciiGrid {
-            contents,
-            width,
-            height,
-        })
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 #[repr(u8)]
 pub enum Direction {
     Right = 0,
@@ -94,6 +328,20 @@ pub enum Direction {
 }
 
 impl Direction {
+    fn from_index(index: u8) -> Direction {
+        match index & 7 {
+            0 => Direction::Right,
+            1 => Direction::RightDown,
+            2 => Direction::Down,
+            3 => Direction::LeftDown,
+            4 => Direction::Left,
+            5 => Direction::LeftUp,
+            6 => Direction::Up,
+            7 => Direction::RightUp,
+            _ => unreachable!(),
+        }
+    }
+
     pub fn all() -> impl Iterator<Item = Direction> {
         struct All(u8);
 
@@ -105,17 +353,7 @@ impl Direction {
                     return None
                 }
 
-                let value = match self.0 {
-                    0 => Direction::Right,
-                    1 => Direction::RightDown,
-                    2 => Direction::Down,
-                    3 => Direction::LeftDown,
-                    4 => Direction::Left,
-                    5 => Direction::LeftUp,
-                    6 => Direction::Up,
-                    7 => Direction::RightUp,
-                    _ => unreachable!(),
-                };
+                let value = Direction::from_index(self.0);
                 self.0 += 1;
                 Some(value)
             }
@@ -124,6 +362,52 @@ impl Direction {
         All(0)
     }
 
+    pub fn cardinal() -> impl DoubleEndedIterator<Item = Direction> + ExactSizeIterator + Clone {
+        [Direction::Right, Direction::Down, Direction::Left, Direction::Up].into_iter()
+    }
+
+    pub fn diagonal() -> impl DoubleEndedIterator<Item = Direction> + ExactSizeIterator + Clone {
+        [Direction::RightDown, Direction::LeftDown, Direction::LeftUp, Direction::RightUp].into_iter()
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        (self as u8) & 1 != 0
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::from_index(self as u8 + 4)
+    }
+
+    /// Rotate 90° anticlockwise, as seen with y pointing down the screen.
+    pub fn turn_left(self) -> Direction {
+        Direction::from_index(self as u8 + 6)
+    }
+
+    /// Rotate 90° clockwise, as seen with y pointing down the screen.
+    pub fn turn_right(self) -> Direction {
+        Direction::from_index(self as u8 + 2)
+    }
+
+    pub fn turn_left_45(self) -> Direction {
+        Direction::from_index(self as u8 + 7)
+    }
+
+    pub fn turn_right_45(self) -> Direction {
+        Direction::from_index(self as u8 + 1)
+    }
+
+    /// Parse a cardinal direction from an arrow (`^>v<`), a `UDLR` letter or a
+    /// `NESW` compass letter. Letters are case-insensitive.
+    pub fn from_byte(b: u8) -> Option<Direction> {
+        match b {
+            b'>' | b'R' | b'r' | b'E' | b'e' => Some(Direction::Right),
+            b'v' | b'D' | b'd' | b'S' | b's' => Some(Direction::Down),
+            b'<' | b'L' | b'l' | b'W' | b'w' => Some(Direction::Left),
+            b'^' | b'U' | b'u' | b'N' | b'n' => Some(Direction::Up),
+            _ => None,
+        }
+    }
+
     pub fn delta(self) -> IVec2 {
         match self {
             Direction::Right => IVec2::new(1, 0),
@@ -137,3 +421,41 @@ impl Direction {
         }
     }
 }
+
+impl TryFrom<u8> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Direction::from_byte(value)
+            .ok_or_else(|| anyhow!("invalid direction {:?}", value as char))
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        u8::try_from(value).ok()
+            .and_then(Direction::from_byte)
+            .ok_or_else(|| anyhow!("invalid direction {value:?}"))
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    /// Parse a single direction character (see [`Direction::from_byte`]), or a
+    /// two-letter compass point such as `NE` for the diagonals.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NE" => Ok(Direction::RightUp),
+            "SE" => Ok(Direction::RightDown),
+            "SW" => Ok(Direction::LeftDown),
+            "NW" => Ok(Direction::LeftUp),
+            _ => match s.as_bytes() {
+                &[b] => Direction::try_from(b),
+                _ => bail!("invalid direction {s:?}"),
+            },
+        }
+    }
+}
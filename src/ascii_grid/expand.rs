@@ -0,0 +1,160 @@
+use glam::{I64Vec2, IVec2};
+
+use super::Grid;
+
+impl<T> Grid<T> {
+    /// Insert a row filled with `fill` before row `y`, so that it becomes row
+    /// `y`. Panics if `y > height`.
+    pub fn insert_row(&mut self, y: usize, fill: T) where T: Clone {
+        assert!(y <= self.height, "row {y} out of bounds");
+        let offset = y * self.width;
+        self.contents.splice(offset..offset, std::iter::repeat_n(fill, self.width));
+        self.height += 1;
+    }
+
+    /// Insert a column filled with `fill` before column `x`, so that it
+    /// becomes column `x`. Panics if `x > width`.
+    pub fn insert_column(&mut self, x: usize, fill: T) where T: Clone {
+        assert!(x <= self.width, "column {x} out of bounds");
+        let mut contents = Vec::with_capacity((self.width + 1) * self.height);
+        for row in self.contents.chunks_exact(self.width.max(1)).take(self.height) {
+            contents.extend_from_slice(&row[..x]);
+            contents.push(fill.clone());
+            contents.extend_from_slice(&row[x..]);
+        }
+        if self.width == 0 {
+            contents.resize(self.height, fill);
+        }
+
+        self.contents = contents;
+        self.width += 1;
+    }
+
+    /// Remove row `y`, returning its contents. Panics if `y >= height`.
+    pub fn remove_row(&mut self, y: usize) -> Vec<T> {
+        assert!(y < self.height, "row {y} out of bounds");
+        let offset = y * self.width;
+        let row = self.contents.drain(offset..offset + self.width).collect();
+        self.height -= 1;
+        row
+    }
+
+    /// Remove column `x`, returning its contents. Panics if `x >= width`.
+    pub fn remove_column(&mut self, x: usize) -> Vec<T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        let mut column = Vec::with_capacity(self.height);
+        let mut contents = Vec::with_capacity((self.width - 1) * self.height);
+        for (i, t) in std::mem::take(&mut self.contents).into_iter().enumerate() {
+            if i % self.width == x {
+                column.push(t);
+            } else {
+                contents.push(t);
+            }
+        }
+
+        self.contents = contents;
+        self.width -= 1;
+        column
+    }
+
+    /// Build a copy of the grid where every row and column made up entirely
+    /// of cells matching `is_empty` is repeated `factor` times.
+    pub fn expand_empty(&self, factor: usize, mut is_empty: impl FnMut(&T) -> bool) -> Grid<T> where T: Clone {
+        let (empty_columns, empty_rows) = self.empty_lines(&mut is_empty);
+        let repeats = |empty: bool| if empty { factor } else { 1 };
+
+        let width = empty_columns.iter().map(|&e| repeats(e)).sum();
+        let height = empty_rows.iter().map(|&e| repeats(e)).sum();
+        let mut contents = Vec::with_capacity(width * height);
+
+        for (row, &empty_row) in self.rows().zip(&empty_rows) {
+            let copies = repeats(empty_row);
+            if copies == 0 {
+                continue;
+            }
+
+            let start = contents.len();
+            for (t, &empty_column) in row.iter().zip(&empty_columns) {
+                contents.extend(std::iter::repeat_n(t, repeats(empty_column)).cloned());
+            }
+            for _ in 1..copies {
+                contents.extend_from_within(start..start + width);
+            }
+        }
+
+        Grid {
+            contents,
+            width,
+            height,
+        }
+    }
+
+    /// Work out where each row and column would end up if every empty row
+    /// and column were repeated `factor` times, without building the
+    /// expanded grid.
+    pub fn expansion(&self, factor: i64, mut is_empty: impl FnMut(&T) -> bool) -> Expansion {
+        let (empty_columns, empty_rows) = self.empty_lines(&mut is_empty);
+        Expansion {
+            columns: offsets(&empty_columns, factor),
+            rows: offsets(&empty_rows, factor),
+        }
+    }
+
+    fn empty_lines(&self, is_empty: &mut impl FnMut(&T) -> bool) -> (Vec<bool>, Vec<bool>) {
+        let mut columns = vec![true; self.width];
+        let mut rows = vec![true; self.height];
+        for (y, row) in self.rows().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                if !is_empty(t) {
+                    columns[x] = false;
+                    rows[y] = false;
+                }
+            }
+        }
+        (columns, rows)
+    }
+}
+
+/// The start of each line once the empty ones are expanded, followed by the
+/// total expanded length.
+fn offsets(empty: &[bool], factor: i64) -> Vec<i64> {
+    let mut offsets = Vec::with_capacity(empty.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for &e in empty {
+        offset += if e { factor } else { 1 };
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// The expanded offsets of each row and column of a grid, as computed by
+/// [`Grid::expansion`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Expansion {
+    columns: Vec<i64>,
+    rows: Vec<i64>,
+}
+
+impl Expansion {
+    /// The expanded offset of each column.
+    pub fn columns(&self) -> &[i64] {
+        &self.columns[..self.columns.len() - 1]
+    }
+
+    /// The expanded offset of each row.
+    pub fn rows(&self) -> &[i64] {
+        &self.rows[..self.rows.len() - 1]
+    }
+
+    /// The size of the expanded grid.
+    pub fn size(&self) -> I64Vec2 {
+        I64Vec2::new(*self.columns.last().unwrap(), *self.rows.last().unwrap())
+    }
+
+    /// Map a position in the original grid to the expanded grid. Panics if
+    /// `at` is out of bounds.
+    pub fn map(&self, at: IVec2) -> I64Vec2 {
+        I64Vec2::new(self.columns()[at.x as usize], self.rows()[at.y as usize])
+    }
+}
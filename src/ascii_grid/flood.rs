@@ -0,0 +1,252 @@
+use std::collections::VecDeque;
+
+use glam::IVec2;
+
+use super::{Direction, Grid};
+
+/// Which neighbours count as connected when filling or labelling.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Connectivity {
+    Four,
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(self) -> impl Iterator<Item = Direction> {
+        Direction::all()
+            .filter(move |d| self == Connectivity::Eight || !d.is_diagonal())
+    }
+}
+
+/// A single connected component found by [`Grid::components`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Region {
+    area: usize,
+    perimeter: usize,
+    min: IVec2,
+    max: IVec2,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.area
+    }
+
+    /// The number of cell edges on the boundary of the region, counting only
+    /// orthogonal neighbours regardless of the connectivity used.
+    pub fn perimeter(&self) -> usize {
+        self.perimeter
+    }
+
+    /// The inclusive minimum corner of the bounding box.
+    pub fn min(&self) -> IVec2 {
+        self.min
+    }
+
+    /// The inclusive maximum corner of the bounding box.
+    pub fn max(&self) -> IVec2 {
+        self.max
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.max - self.min + IVec2::ONE
+    }
+}
+
+/// The result of labelling a grid: a label per cell, indexing into the list
+/// of regions.
+#[derive(Clone)]
+pub struct Components {
+    labels: Grid<Option<usize>>,
+    regions: Vec<Region>,
+}
+
+impl Components {
+    pub fn labels(&self) -> &Grid<Option<usize>> {
+        &self.labels
+    }
+
+    pub fn label(&self, at: IVec2) -> Option<usize> {
+        self.labels.get(at).flatten()
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn region_at(&self, at: IVec2) -> Option<&Region> {
+        self.label(at).map(|l| &self.regions[l])
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Find every cell reachable from `seed` through cells for which `include`
+    /// returns true, in breadth-first order.
+    pub fn flood_fill(
+        &self,
+        seed: IVec2,
+        connectivity: Connectivity,
+        mut include: impl FnMut(IVec2, &T) -> bool,
+    ) -> Vec<IVec2> {
+        let mut visited = Grid::new(self.width, self.height, false);
+        let mut queue = VecDeque::new();
+        let mut filled = Vec::new();
+
+        if self.get_ref(seed).is_some_and(|t| include(seed, t)) {
+            visited[seed] = true;
+            queue.push_back(seed);
+        }
+
+        while let Some(p) = queue.pop_front() {
+            filled.push(p);
+
+            for d in connectivity.directions() {
+                let q = p + d.delta();
+                if visited.get(q) != Some(false) || !include(q, &self[q]) {
+                    continue;
+                }
+
+                visited[q] = true;
+                queue.push_back(q);
+            }
+        }
+
+        filled
+    }
+
+    /// For every cell, the number of steps to the nearest cell for which
+    /// `is_source` returns true, or `None` if there are no such cells. With
+    /// [`Connectivity::Four`] this is the Manhattan distance, and with
+    /// [`Connectivity::Eight`] the Chebyshev distance.
+    pub fn distance_transform(
+        &self,
+        connectivity: Connectivity,
+        mut is_source: impl FnMut(IVec2, &T) -> bool,
+    ) -> Grid<Option<u32>> {
+        let mut distances = Grid::new(self.width, self.height, None);
+        let mut queue = VecDeque::new();
+
+        for (i, t) in self.contents.iter().enumerate() {
+            let p = self.from_offset(i).unwrap();
+            if is_source(p, t) {
+                distances[p] = Some(0);
+                queue.push_back(p);
+            }
+        }
+
+        while let Some(p) = queue.pop_front() {
+            let distance = distances[p].unwrap() + 1;
+
+            for d in connectivity.directions() {
+                let q = p + d.delta();
+                if distances.get(q) != Some(None) {
+                    continue;
+                }
+
+                distances[q] = Some(distance);
+                queue.push_back(q);
+            }
+        }
+
+        distances
+    }
+
+    /// Replace the region of cells equal to the one at `seed` with `value`,
+    /// returning the number of cells changed.
+    pub fn fill_region(&mut self, seed: IVec2, connectivity: Connectivity, value: T) -> usize
+        where T: PartialEq + Clone
+    {
+        let Some(target) = self.get_ref(seed).cloned() else {
+            return 0;
+        };
+
+        if target == value {
+            return 0;
+        }
+
+        let filled = self.flood_fill(seed, connectivity, |_, t| *t == target);
+        for &p in &filled {
+            self[p] = value.clone();
+        }
+
+        filled.len()
+    }
+
+    /// Label the connected components formed by cells for which `include`
+    /// returns true. Other cells are left unlabelled.
+    pub fn components(&self, connectivity: Connectivity, include: impl FnMut(&T) -> bool) -> Components {
+        self.components_by(connectivity, include, |_, _| true)
+    }
+
+    /// Label every cell, joining neighbouring cells when `same` returns true
+    /// for them, e.g. `|a, b| a == b` to find regions of identical tiles.
+    pub fn components_eq(&self, connectivity: Connectivity, same: impl FnMut(&T, &T) -> bool) -> Components {
+        self.components_by(connectivity, |_| true, same)
+    }
+
+    fn components_by(
+        &self,
+        connectivity: Connectivity,
+        mut include: impl FnMut(&T) -> bool,
+        mut same: impl FnMut(&T, &T) -> bool,
+    ) -> Components {
+        let mut labels = Grid::new(self.width, self.height, None);
+        let mut regions = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for seed in self.positions() {
+            if labels[seed].is_some() || !include(&self[seed]) {
+                continue;
+            }
+
+            let label = regions.len();
+            let mut region = Region {
+                area: 0,
+                perimeter: 0,
+                min: seed,
+                max: seed,
+            };
+
+            labels[seed] = Some(label);
+            queue.push_back(seed);
+
+            while let Some(p) = queue.pop_front() {
+                region.area += 1;
+                region.min = region.min.min(p);
+                region.max = region.max.max(p);
+
+                for d in Connectivity::Four.directions() {
+                    let q = p + d.delta();
+                    if !self.get_ref(q).is_some_and(|t| include(t) && same(&self[p], t)) {
+                        region.perimeter += 1;
+                    }
+                }
+
+                for d in connectivity.directions() {
+                    let q = p + d.delta();
+                    if labels.get(q) != Some(None) || !include(&self[q]) || !same(&self[p], &self[q]) {
+                        continue;
+                    }
+
+                    labels[q] = Some(label);
+                    queue.push_back(q);
+                }
+            }
+
+            regions.push(region);
+        }
+
+        Components {
+            labels,
+            regions,
+        }
+    }
+}
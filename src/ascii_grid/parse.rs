@@ -0,0 +1,313 @@
+use std::io::{self, BufRead};
+
+use thiserror::Error;
+
+use super::AsciiGrid;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Error)]
+pub enum ParseGridError {
+    #[error("empty grid")]
+    Empty,
+    #[error("line {line} is too short: expected width {expected}, got {actual}")]
+    LineTooShort {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("line {line} is too long: expected width {expected}, got {actual}")]
+    LineTooLong {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("unexpected blank line {line}")]
+    UnexpectedBlankLine {
+        line: usize,
+    },
+}
+
+/// An error reading a grid from a stream: either the read itself failed, or
+/// the data read was not a valid grid.
+#[derive(Debug, Error)]
+pub enum ReadGridError {
+    #[error("failed to read grid: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Parse(#[from] ParseGridError),
+}
+
+/// How a [`GridParser`] treats blank lines between rows. Leading and trailing
+/// blank lines are always ignored.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum BlankLines {
+    /// Drop blank lines, joining the rows either side into one grid.
+    #[default]
+    Skip,
+    /// Treat blank lines as the boundary between separate grids.
+    Separator,
+    /// Fail on any blank line.
+    Error,
+}
+
+/// Options for parsing [`AsciiGrid`]s from text.
+///
+/// The defaults match [`AsciiGrid::try_from`]: lines are trimmed, blank lines
+/// are skipped and every line must be the same width.
+#[derive(Debug, Clone, Copy)]
+pub struct GridParser {
+    trim: bool,
+    blank_lines: BlankLines,
+    pad: Option<u8>,
+}
+
+impl Default for GridParser {
+    fn default() -> Self {
+        GridParser {
+            trim: true,
+            blank_lines: BlankLines::Skip,
+            pad: None,
+        }
+    }
+}
+
+impl GridParser {
+    pub fn new() -> GridParser {
+        GridParser::default()
+    }
+
+    /// Whether to strip leading and trailing whitespace from each line. When
+    /// disabled, only entirely empty lines are blank.
+    pub fn trim(mut self, trim: bool) -> GridParser {
+        self.trim = trim;
+        self
+    }
+
+    pub fn blank_lines(mut self, blank_lines: BlankLines) -> GridParser {
+        self.blank_lines = blank_lines;
+        self
+    }
+
+    /// Pad short lines with `fill` up to the width of the longest line,
+    /// instead of failing.
+    pub fn pad(mut self, fill: u8) -> GridParser {
+        self.pad = Some(fill);
+        self
+    }
+
+    /// Parse exactly one grid.
+    pub fn parse(&self, value: &str) -> Result<AsciiGrid, ParseGridError> {
+        let blank_lines = match self.blank_lines {
+            BlankLines::Separator => BlankLines::Error,
+            blank_lines => blank_lines,
+        };
+        let block = GridParser { blank_lines, ..*self }
+            .blocks(value)?
+            .pop()
+            .ok_or(ParseGridError::Empty)?;
+        self.build(&block)
+    }
+
+    /// Parse every grid in `value`. Unless blank lines are separators, this
+    /// will contain at most one grid.
+    pub fn parse_many(&self, value: &str) -> Result<Vec<AsciiGrid>, ParseGridError> {
+        self.blocks(value)?
+            .iter()
+            .map(|block| self.build(block))
+            .collect()
+    }
+
+    /// Parse exactly one grid, reading it line by line from `reader`.
+    pub fn parse_reader(&self, mut reader: impl BufRead) -> Result<AsciiGrid, ReadGridError> {
+        let mut builder = GridBuilder::new(self.pad);
+        let mut pending_blank = None;
+        let mut buffer = Vec::new();
+        let mut line_number = 0;
+
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            let mut line = buffer.strip_suffix(b"\n").unwrap_or(&buffer);
+            line = line.strip_suffix(b"\r").unwrap_or(line);
+            if self.trim {
+                line = trim_line(line);
+            }
+
+            if line.is_empty() {
+                if builder.height > 0 && pending_blank.is_none() {
+                    pending_blank = Some(line_number);
+                }
+                continue;
+            }
+
+            if let Some(blank) = pending_blank.take() {
+                if self.blank_lines != BlankLines::Skip {
+                    return Err(ParseGridError::UnexpectedBlankLine {
+                        line: blank,
+                    }.into());
+                }
+            }
+
+            builder.push(line_number, line)?;
+        }
+
+        Ok(builder.finish()?)
+    }
+
+    fn blocks<'a>(&self, value: &'a str) -> Result<Vec<Vec<(usize, &'a str)>>, ParseGridError> {
+        let mut blocks = Vec::new();
+        let mut current = Vec::new();
+        let mut pending_blank = None;
+
+        for (i, line) in value.lines().enumerate() {
+            let line_number = i + 1;
+            let line = if self.trim { line.trim() } else { line };
+
+            if line.is_empty() {
+                if !current.is_empty() && pending_blank.is_none() {
+                    pending_blank = Some(line_number);
+                }
+                continue;
+            }
+
+            if let Some(blank) = pending_blank.take() {
+                match self.blank_lines {
+                    BlankLines::Skip => {},
+                    BlankLines::Separator => blocks.push(std::mem::take(&mut current)),
+                    BlankLines::Error => return Err(ParseGridError::UnexpectedBlankLine {
+                        line: blank,
+                    }),
+                }
+            }
+
+            current.push((line_number, line));
+        }
+
+        if !current.is_empty() {
+            blocks.push(current);
+        }
+
+        Ok(blocks)
+    }
+
+    fn build(&self, lines: &[(usize, &str)]) -> Result<AsciiGrid, ParseGridError> {
+        let mut builder = GridBuilder::new(self.pad);
+        for &(line, s) in lines {
+            builder.push(line, s.as_bytes())?;
+        }
+        builder.finish()
+    }
+}
+
+/// Strip leading and trailing whitespace the same way as [`str::trim`], so
+/// that reading a grid matches parsing it from a string. Lines which aren't
+/// valid UTF-8 only have ASCII whitespace removed.
+fn trim_line(line: &[u8]) -> &[u8] {
+    match std::str::from_utf8(line) {
+        Ok(s) => s.trim().as_bytes(),
+        Err(_) => line.trim_ascii(),
+    }
+}
+
+/// Accumulates rows of a grid, checking their widths as they arrive.
+struct GridBuilder {
+    pad: Option<u8>,
+    contents: Vec<u8>,
+    row_widths: Vec<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl GridBuilder {
+    fn new(pad: Option<u8>) -> GridBuilder {
+        GridBuilder {
+            pad,
+            contents: Vec::new(),
+            row_widths: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+
+    fn push(&mut self, line: usize, row: &[u8]) -> Result<(), ParseGridError> {
+        if self.pad.is_some() {
+            // Short rows are padded in finish(), once the widest is known.
+            self.width = self.width.max(row.len());
+            self.row_widths.push(row.len());
+        } else if self.height == 0 {
+            self.width = row.len();
+        } else if row.len() > self.width {
+            return Err(ParseGridError::LineTooLong {
+                line,
+                expected: self.width,
+                actual: row.len(),
+            });
+        } else if row.len() < self.width {
+            return Err(ParseGridError::LineTooShort {
+                line,
+                expected: self.width,
+                actual: row.len(),
+            });
+        }
+
+        self.contents.extend_from_slice(row);
+        self.height += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<AsciiGrid, ParseGridError> {
+        if self.height == 0 {
+            return Err(ParseGridError::Empty);
+        }
+
+        let contents = match self.pad {
+            Some(fill) if self.row_widths.iter().any(|&w| w != self.width) => {
+                let mut padded = Vec::with_capacity(self.width * self.height);
+                let mut rest = &self.contents[..];
+                for &w in &self.row_widths {
+                    padded.extend_from_slice(&rest[..w]);
+                    padded.resize(padded.len() + self.width - w, fill);
+                    rest = &rest[w..];
+                }
+                padded
+            },
+            _ => self.contents,
+        };
+
+        Ok(AsciiGrid {
+            contents,
+            width: self.width,
+            height: self.height,
+        })
+    }
+}
+
+impl AsciiGrid {
+    /// Parse a grid whose lines may differ in length, padding short lines
+    /// with `fill` up to the width of the longest.
+    pub fn parse_padded(value: &str, fill: u8) -> Result<AsciiGrid, ParseGridError> {
+        GridParser::new().pad(fill).parse(value)
+    }
+
+    /// Read a grid line by line, with the same rules as
+    /// [`AsciiGrid::try_from`].
+    pub fn from_reader(reader: impl BufRead) -> Result<AsciiGrid, ReadGridError> {
+        GridParser::new().parse_reader(reader)
+    }
+
+    /// Parse several grids separated by blank lines.
+    pub fn parse_many(value: &str) -> Result<Vec<AsciiGrid>, ParseGridError> {
+        GridParser::new().blank_lines(BlankLines::Separator).parse_many(value)
+    }
+}
+
+impl TryFrom<&str> for AsciiGrid {
+    type Error = ParseGridError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        GridParser::new().parse(value)
+    }
+}
@@ -0,0 +1,84 @@
+use glam::IVec2;
+
+use super::{Direction, Grid, GridView, Symmetry};
+
+impl<T: PartialEq> Grid<T> {
+    /// Find every occurrence of `word` reading in any of the eight
+    /// directions, returning the position of its first cell and the direction
+    /// it reads in.
+    ///
+    /// Single-cell words are only reported once per position.
+    pub fn find_word(&self, word: &[T]) -> Vec<(IVec2, Direction)> {
+        let Some(first) = word.first() else {
+            return Vec::new();
+        };
+
+        let directions: Vec<_> = if word.len() == 1 {
+            vec![Direction::Right]
+        } else {
+            Direction::all().collect()
+        };
+
+        let mut found = Vec::new();
+        for (start, _) in self.positions().zip(self.iter()).filter(|(_, t)| *t == first) {
+            for &d in &directions {
+                let matches = word.iter()
+                    .enumerate()
+                    .skip(1)
+                    .all(|(i, t)| self.get_ref(start + d.delta() * i as i32) == Some(t));
+                if matches {
+                    found.push((start, d));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Find every position at which `pattern` matches, as the top-left
+    /// corner of the pattern. Cells of the pattern equal to `wildcard` match
+    /// anything.
+    pub fn find_pattern(&self, pattern: &Grid<T>, wildcard: Option<&T>) -> Vec<IVec2> {
+        self.find_view(pattern.view(), wildcard)
+    }
+
+    /// Like [`Grid::find_pattern`], but also tries each of `symmetries` of
+    /// the pattern. Symmetric patterns will be reported once for each
+    /// symmetry that matches.
+    pub fn find_pattern_with(
+        &self,
+        pattern: &Grid<T>,
+        wildcard: Option<&T>,
+        symmetries: &[Symmetry],
+    ) -> Vec<(IVec2, Symmetry)> {
+        symmetries.iter()
+            .flat_map(|&s| self.find_view(pattern.view().apply(s), wildcard)
+                .into_iter()
+                .map(move |p| (p, s)))
+            .collect()
+    }
+
+    fn find_view(&self, pattern: GridView<'_, T>, wildcard: Option<&T>) -> Vec<IVec2> {
+        let range = self.size() - pattern.size() + IVec2::ONE;
+        if pattern.size().cmple(IVec2::ZERO).any() || range.cmple(IVec2::ZERO).any() {
+            return Vec::new();
+        }
+
+        let mut found = Vec::new();
+        for y in 0..range.y {
+            for x in 0..range.x {
+                let at = IVec2::new(x, y);
+                let matches = pattern.positions()
+                    .all(|p| {
+                        let expected = pattern.get_ref(p).unwrap();
+                        Some(expected) == wildcard || self.get_ref(at + p) == Some(expected)
+                    });
+                if matches {
+                    found.push(at);
+                }
+            }
+        }
+
+        found
+    }
+}
@@ -0,0 +1,197 @@
+use glam::IVec2;
+
+use super::{AsciiGrid, Direction};
+
+/// A set of directions, stored as a bitmask indexed by [`Direction`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Connections(u8);
+
+impl Connections {
+    pub const NONE: Connections = Connections(0);
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(self, direction: Direction) -> bool {
+        self.0 & (1 << direction as u8) != 0
+    }
+
+    pub fn with(self, direction: Direction) -> Connections {
+        Connections(self.0 | (1 << direction as u8))
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The directions in the set, in [`Direction::all`] order.
+    pub fn iter(self) -> impl Iterator<Item = Direction> {
+        Direction::all().filter(move |&d| self.contains(d))
+    }
+}
+
+impl FromIterator<Direction> for Connections {
+    fn from_iter<I: IntoIterator<Item = Direction>>(iter: I) -> Self {
+        iter.into_iter().fold(Connections::NONE, Connections::with)
+    }
+}
+
+/// A table of which directions each tile byte connects to, for following
+/// pipes through a maze.
+///
+/// The default table has the usual pipes: `|`, `-`, `L`, `J`, `7` and `F`.
+/// Any byte not in the table, such as `.` or a start marker, connects to
+/// nothing.
+#[derive(Debug, Clone)]
+pub struct PipeTiles {
+    connections: [Connections; 256],
+}
+
+impl Default for PipeTiles {
+    fn default() -> Self {
+        use Direction::*;
+
+        PipeTiles::empty()
+            .tile(b'|', &[Up, Down])
+            .tile(b'-', &[Left, Right])
+            .tile(b'L', &[Up, Right])
+            .tile(b'J', &[Up, Left])
+            .tile(b'7', &[Down, Left])
+            .tile(b'F', &[Down, Right])
+    }
+}
+
+impl PipeTiles {
+    pub fn new() -> PipeTiles {
+        PipeTiles::default()
+    }
+
+    /// A table where no tile connects to anything.
+    pub fn empty() -> PipeTiles {
+        PipeTiles {
+            connections: [Connections::NONE; 256],
+        }
+    }
+
+    /// Set the directions `tile` connects to.
+    pub fn tile(mut self, tile: u8, directions: &[Direction]) -> PipeTiles {
+        self.connections[tile as usize] = directions.iter().copied().collect();
+        self
+    }
+
+    pub fn connections(&self, tile: u8) -> Connections {
+        self.connections[tile as usize]
+    }
+
+    /// The first tile in the table with exactly `connections`.
+    pub fn tile_for(&self, connections: Connections) -> Option<u8> {
+        if connections.is_empty() {
+            return None;
+        }
+
+        (0..=u8::MAX).find(|&b| self.connections(b) == connections)
+    }
+
+    /// Whether the cell at `at` and its neighbour in `direction` both
+    /// connect to each other.
+    pub fn connects(&self, grid: &AsciiGrid, at: IVec2, direction: Direction) -> bool {
+        let (Some(a), Some(b)) = (grid.get(at), grid.get(at + direction.delta())) else {
+            return false;
+        };
+
+        self.connections(a).contains(direction)
+            && self.connections(b).contains(direction.opposite())
+    }
+
+    /// The directions in which the neighbours of `at` connect back to it,
+    /// whatever the tile at `at` itself is.
+    pub fn connected_neighbours(&self, grid: &AsciiGrid, at: IVec2) -> Connections {
+        Direction::all()
+            .filter(|&d| grid.get(at + d.delta())
+                .is_some_and(|b| self.connections(b).contains(d.opposite())))
+            .collect()
+    }
+
+    /// Work out the tile hidden under a marker at `at`: the tile connecting
+    /// two of the neighbours which point at it, such that the pipes form a
+    /// closed loop through `at`. Returns `None` if no tile in the table fits.
+    pub fn infer(&self, grid: &AsciiGrid, at: IVec2) -> Option<u8> {
+        self.infer_loop(grid, at).map(|(tile, _)| tile)
+    }
+
+    /// Like [`PipeTiles::infer`], but also returning the loop, as
+    /// [`PipeTiles::trace_loop`] would.
+    pub fn infer_loop(&self, grid: &AsciiGrid, at: IVec2) -> Option<(u8, Vec<IVec2>)> {
+        let candidates = self.connected_neighbours(grid, at);
+
+        // Other pipes may happen to point at the marker without being part of
+        // the loop, so try each pair in turn.
+        for (i, a) in candidates.iter().enumerate() {
+            for b in candidates.iter().skip(i + 1) {
+                let connections = Connections::NONE.with(a).with(b);
+                let Some(tile) = self.tile_for(connections) else {
+                    continue;
+                };
+
+                if let Some(path) = self.trace(grid, at, connections) {
+                    return Some((tile, path));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Follow the loop of pipes through `start`, returning every position on
+    /// it in order, beginning with `start`.
+    ///
+    /// If the tile at `start` doesn't connect to anything (e.g. it is a start
+    /// marker), the tile underneath is inferred. Returns `None` if the pipes
+    /// don't form a closed loop.
+    pub fn trace_loop(&self, grid: &AsciiGrid, start: IVec2) -> Option<Vec<IVec2>> {
+        let connections = self.connections(grid.get(start)?);
+        if connections.is_empty() {
+            return self.infer_loop(grid, start).map(|(_, path)| path);
+        }
+
+        self.trace(grid, start, connections)
+    }
+
+    /// Follow the pipes out of `start`, treating it as connecting to
+    /// `connections`.
+    fn trace(&self, grid: &AsciiGrid, start: IVec2, connections: Connections) -> Option<Vec<IVec2>> {
+        let mut direction = connections.iter().next()?;
+        let mut at = start;
+        let mut path = Vec::new();
+
+        loop {
+            path.push(at);
+            at += direction.delta();
+            if at == start {
+                return connections.contains(direction.opposite()).then_some(path);
+            }
+
+            let tile = self.connections(grid.get(at)?);
+            if !tile.contains(direction.opposite()) || tile.len() != 2 {
+                return None;
+            }
+
+            direction = tile.iter().find(|&d| d != direction.opposite())?;
+        }
+    }
+}
+
+impl AsciiGrid {
+    /// Find the `start` marker and trace the pipe loop through it with the
+    /// default [`PipeTiles`], returning the tile under the marker and the
+    /// loop.
+    pub fn pipe_loop(&self, start: u8) -> Option<(u8, Vec<IVec2>)> {
+        let at = self.position_of(start)?;
+        PipeTiles::new().infer_loop(self, at)
+    }
+}
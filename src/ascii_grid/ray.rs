@@ -0,0 +1,77 @@
+use std::iter::FusedIterator;
+
+use glam::IVec2;
+
+use super::{Direction, Grid};
+
+/// Iterator over the cells of a grid in a straight line, starting with the
+/// origin and continuing until it leaves the grid. Created by [`Grid::ray`].
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    at: IVec2,
+    delta: IVec2,
+}
+
+impl<'a, T: Copy> Iterator for Ray<'a, T> {
+    type Item = (IVec2, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.grid.get(self.at)?;
+        let at = self.at;
+        self.at += self.delta;
+        Some((at, value))
+    }
+}
+
+impl<'a, T: Copy> FusedIterator for Ray<'a, T> {}
+
+impl<T: Copy> Grid<T> {
+    pub fn ray(&self, start: IVec2, direction: Direction) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            at: start,
+            delta: direction.delta(),
+        }
+    }
+
+    /// Walk from `start` (inclusive) in `direction` for as long as `predicate`
+    /// holds.
+    pub fn walk_while<'a>(
+        &'a self,
+        start: IVec2,
+        direction: Direction,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (IVec2, T)> + 'a {
+        self.ray(start, direction).take_while(move |(_, t)| predicate(t))
+    }
+
+    /// The first cell after `start` in `direction` for which `predicate`
+    /// holds.
+    pub fn first_hit(
+        &self,
+        start: IVec2,
+        direction: Direction,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> Option<(IVec2, T)> {
+        self.ray(start, direction).skip(1).find(|(_, t)| predicate(t))
+    }
+
+    /// Count the cells visible from `start` looking in `direction`: every
+    /// cell up to and including the first one which `blocks` the view.
+    pub fn count_visible(&self, start: IVec2, direction: Direction, mut blocks: impl FnMut(&T) -> bool) -> usize {
+        let mut count = 0;
+        for (_, t) in self.ray(start, direction).skip(1) {
+            count += 1;
+            if blocks(&t) {
+                break;
+            }
+        }
+        count
+    }
+
+    /// Whether `start` can see out of the grid in `direction` without any
+    /// cell in between blocking the view.
+    pub fn sees_edge(&self, start: IVec2, direction: Direction, mut blocks: impl FnMut(&T) -> bool) -> bool {
+        self.ray(start, direction).skip(1).all(|(_, t)| !blocks(&t))
+    }
+}
@@ -0,0 +1,126 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use glam::IVec2;
+
+use super::{AsciiGrid, Connectivity, Grid};
+
+/// A horizontal run of consecutive matching bytes within a single row.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Span<'a> {
+    row: usize,
+    range: Range<usize>,
+    bytes: &'a [u8],
+}
+
+impl<'a> Span<'a> {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The columns covered by the span.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    pub fn start(&self) -> IVec2 {
+        IVec2::new(self.range.start as i32, self.row as i32)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.bytes).ok()
+    }
+
+    pub fn parse<F: FromStr>(&self) -> Option<F> {
+        self.as_str()?.parse().ok()
+    }
+
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = IVec2> + ExactSizeIterator {
+        let y = self.row as i32;
+        self.range.clone().map(move |x| IVec2::new(x as i32, y))
+    }
+}
+
+/// Every [`Span`] of a grid matching some predicate, along with an index from
+/// cells back to the span covering them.
+#[derive(Clone)]
+pub struct Runs<'a> {
+    spans: Vec<Span<'a>>,
+    index: Grid<Option<usize>>,
+}
+
+impl<'a> Runs<'a> {
+    pub fn spans(&self) -> &[Span<'a>] {
+        &self.spans
+    }
+
+    /// The index of the span covering `at`, if any.
+    pub fn span_index(&self, at: IVec2) -> Option<usize> {
+        self.index.get(at).flatten()
+    }
+
+    pub fn span_at(&self, at: IVec2) -> Option<&Span<'a>> {
+        self.span_index(at).map(|i| &self.spans[i])
+    }
+
+    /// The indices of every distinct span touching a neighbour of `at`, in
+    /// ascending order. A span covering `at` itself is not included.
+    pub fn adjacent(&self, at: IVec2, connectivity: Connectivity) -> Vec<usize> {
+        let own = self.span_index(at);
+        let mut found: Vec<_> = connectivity.directions()
+            .filter_map(|d| self.span_index(at + d.delta()))
+            .filter(|&i| Some(i) != own)
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+impl AsciiGrid {
+    /// Find every maximal horizontal run of bytes for which `predicate`
+    /// returns true, e.g. `|b| b.is_ascii_digit()` to find numbers.
+    pub fn runs(&self, mut predicate: impl FnMut(u8) -> bool) -> Runs<'_> {
+        let mut spans = Vec::new();
+        let mut index = Grid::new(self.width, self.height, None);
+
+        for (row, bytes) in self.rows().enumerate() {
+            let mut x = 0;
+            while x < bytes.len() {
+                if !predicate(bytes[x]) {
+                    x += 1;
+                    continue;
+                }
+
+                let start = x;
+                while x < bytes.len() && predicate(bytes[x]) {
+                    index[IVec2::new(x as i32, row as i32)] = Some(spans.len());
+                    x += 1;
+                }
+
+                spans.push(Span {
+                    row,
+                    range: start..x,
+                    bytes: &bytes[start..x],
+                });
+            }
+        }
+
+        Runs {
+            spans,
+            index,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::fmt::{Debug, Formatter, Write};
+
+use glam::IVec2;
+
+use crate::bounds;
+
+use super::{Grid, GridTile};
+
+/// An unbounded grid which only stores occupied cells.
+///
+/// The bounding box of the occupied cells is tracked as cells are added and
+/// removed.
+#[derive(Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<IVec2, T>,
+    bounds: Option<(IVec2, IVec2)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Build a sparse grid from the cells of `grid` for which `include`
+    /// returns true.
+    pub fn from_grid(grid: &Grid<T>, mut include: impl FnMut(&T) -> bool) -> SparseGrid<T> where T: Clone {
+        grid.positions()
+            .zip(grid.iter())
+            .filter(|(_, t)| include(t))
+            .map(|(p, t)| (p, t.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The inclusive minimum and maximum corners of the occupied cells.
+    pub fn bounds(&self) -> Option<(IVec2, IVec2)> {
+        self.bounds
+    }
+
+    pub fn min(&self) -> Option<IVec2> {
+        self.bounds.map(|(min, _)| min)
+    }
+
+    pub fn max(&self) -> Option<IVec2> {
+        self.bounds.map(|(_, max)| max)
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.bounds.map_or(IVec2::ZERO, |(min, max)| max - min + IVec2::ONE)
+    }
+
+    pub fn contains(&self, at: IVec2) -> bool {
+        self.cells.contains_key(&at)
+    }
+
+    pub fn get(&self, at: IVec2) -> Option<T> where T: Copy {
+        self.cells.get(&at).copied()
+    }
+
+    pub fn get_ref(&self, at: IVec2) -> Option<&T> {
+        self.cells.get(&at)
+    }
+
+    pub fn get_mut(&mut self, at: IVec2) -> Option<&mut T> {
+        self.cells.get_mut(&at)
+    }
+
+    /// Store `value` at `at`, growing the bounds if needed, and returning the
+    /// previous value.
+    pub fn set(&mut self, at: IVec2, value: T) -> Option<T> {
+        self.bounds = Some(bounds::grow(self.bounds, at));
+        self.cells.insert(at, value)
+    }
+
+    pub fn remove(&mut self, at: IVec2) -> Option<T> {
+        let value = self.cells.remove(&at)?;
+
+        self.bounds = bounds::shrink(self.bounds, at, self.cells.keys().copied());
+        Some(value)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, IVec2, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> hash_map::Keys<'_, IVec2, T> {
+        self.cells.keys()
+    }
+
+    /// Convert into a dense grid covering [`SparseGrid::bounds`], filling
+    /// empty cells with `fill`. Cell `(0, 0)` of the result corresponds to
+    /// [`SparseGrid::min`].
+    pub fn to_grid(&self, fill: T) -> Grid<T> where T: Clone {
+        let Some((min, _)) = self.bounds else {
+            return Grid::new(0, 0, fill);
+        };
+
+        let size = self.size();
+        let mut grid = Grid::new(size.x as usize, size.y as usize, fill);
+        for (&p, t) in &self.cells {
+            grid[p - min] = t.clone();
+        }
+
+        grid
+    }
+}
+
+impl<T: Clone> From<&Grid<T>> for SparseGrid<T> {
+    fn from(value: &Grid<T>) -> Self {
+        SparseGrid::from_grid(value, |_| true)
+    }
+}
+
+impl<T> FromIterator<(IVec2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (IVec2, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(IVec2, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (IVec2, T)>>(&mut self, iter: I) {
+        for (p, t) in iter {
+            self.set(p, t);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SparseGrid<T> {
+    type Item = (&'a IVec2, &'a T);
+    type IntoIter = hash_map::Iter<'a, IVec2, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}
+
+/// Empty cells are drawn as `.`.
+impl<T: GridTile> Debug for SparseGrid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let c = self.cells.get(&IVec2::new(x, y)).map_or('.', |t| t.to_char());
+                f.write_char(c)?;
+            }
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
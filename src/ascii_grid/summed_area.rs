@@ -0,0 +1,85 @@
+use glam::IVec2;
+
+use super::Grid;
+
+/// A summed-area table over a grid, answering rectangle sums in constant
+/// time.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SummedArea {
+    /// `sums[y * (width + 1) + x]` is the total of every cell above and to the
+    /// left of `(x, y)`, exclusive.
+    sums: Vec<i64>,
+    width: usize,
+    height: usize,
+}
+
+impl SummedArea {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn corner(&self, at: IVec2) -> i64 {
+        self.sums[at.y as usize * (self.width + 1) + at.x as usize]
+    }
+
+    /// The total weight of the cells from `min` (inclusive) to `max`
+    /// (exclusive). The rectangle is clamped to the grid.
+    pub fn sum(&self, min: IVec2, max: IVec2) -> i64 {
+        let size = IVec2::new(self.width as i32, self.height as i32);
+        let min = min.clamp(IVec2::ZERO, size);
+        let max = max.clamp(min, size);
+
+        self.corner(max) - self.corner(IVec2::new(min.x, max.y))
+            - self.corner(IVec2::new(max.x, min.y)) + self.corner(min)
+    }
+
+    /// The total weight of the whole grid.
+    pub fn total(&self) -> i64 {
+        *self.sums.last().unwrap()
+    }
+
+    pub fn row_sum(&self, y: usize) -> i64 {
+        self.sum(IVec2::new(0, y as i32), IVec2::new(self.width as i32, y as i32 + 1))
+    }
+
+    pub fn column_sum(&self, x: usize) -> i64 {
+        self.sum(IVec2::new(x as i32, 0), IVec2::new(x as i32 + 1, self.height as i32))
+    }
+
+    /// The number of rows whose total weight matches `predicate`.
+    pub fn count_rows(&self, mut predicate: impl FnMut(i64) -> bool) -> usize {
+        (0..self.height).filter(|&y| predicate(self.row_sum(y))).count()
+    }
+
+    /// The number of columns whose total weight matches `predicate`.
+    pub fn count_columns(&self, mut predicate: impl FnMut(i64) -> bool) -> usize {
+        (0..self.width).filter(|&x| predicate(self.column_sum(x))).count()
+    }
+}
+
+impl<T> Grid<T> {
+    /// Build a summed-area table, weighing each cell with `weight`. Use a
+    /// weight of 0 or 1 to count cells matching some condition.
+    pub fn summed_area(&self, mut weight: impl FnMut(&T) -> i64) -> SummedArea {
+        let stride = self.width + 1;
+        let mut sums = vec![0; stride * (self.height + 1)];
+
+        for (y, row) in self.rows().enumerate() {
+            let mut row_total = 0;
+            for (x, t) in row.iter().enumerate() {
+                row_total += weight(t);
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_total;
+            }
+        }
+
+        SummedArea {
+            sums,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
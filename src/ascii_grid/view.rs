@@ -0,0 +1,203 @@
+use std::fmt::{Debug, Formatter, Write};
+
+use glam::IVec2;
+
+use super::{Grid, GridTile};
+
+/// One of the eight ways to rotate and/or flip a rectangle onto itself.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Symmetry {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::RotateCw,
+        Symmetry::Rotate180,
+        Symmetry::RotateCcw,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub const ROTATIONS: [Symmetry; 4] = [
+        Symmetry::Identity,
+        Symmetry::RotateCw,
+        Symmetry::Rotate180,
+        Symmetry::RotateCcw,
+    ];
+}
+
+/// A borrowed, possibly transformed, window onto a [`Grid`].
+///
+/// Views remap coordinates rather than copying cells, so they can be stacked
+/// (e.g. a crop of a rotation) at no cost. Call [`GridView::to_grid`] to
+/// materialise one.
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    origin: IVec2,
+    x_axis: IVec2,
+    y_axis: IVec2,
+    size: IVec2,
+}
+
+impl<'a, T> Clone for GridView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for GridView<'a, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub(super) fn new(grid: &'a Grid<T>) -> GridView<'a, T> {
+        GridView {
+            grid,
+            origin: IVec2::ZERO,
+            x_axis: IVec2::X,
+            y_axis: IVec2::Y,
+            size: grid.size(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.size.x as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.size.y as usize
+    }
+
+    pub fn size(&self) -> IVec2 {
+        self.size
+    }
+
+    pub fn contains(&self, at: IVec2) -> bool {
+        at.cmpge(IVec2::ZERO).all() && at.cmplt(self.size).all()
+    }
+
+    /// Map a position in this view to the underlying grid position.
+    pub fn to_grid_position(&self, at: IVec2) -> Option<IVec2> {
+        if self.contains(at) {
+            Some(self.origin + self.x_axis * at.x + self.y_axis * at.y)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, at: IVec2) -> Option<T> where T: Copy {
+        self.get_ref(at).copied()
+    }
+
+    pub fn get_ref(&self, at: IVec2) -> Option<&'a T> {
+        self.to_grid_position(at).and_then(|p| self.grid.get_ref(p))
+    }
+
+    /// Every position in the view, in row-major order.
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = IVec2> + ExactSizeIterator {
+        let width = self.width();
+        (0..self.width() * self.height())
+            .map(move |i| IVec2::new((i % width) as i32, (i / width) as i32))
+    }
+
+    pub fn enumerate(&self) -> impl DoubleEndedIterator<Item = (IVec2, T)> + ExactSizeIterator + 'a where T: Copy {
+        let view = *self;
+        self.positions().map(move |p| (p, view.get(p).unwrap()))
+    }
+
+    pub fn transpose(&self) -> GridView<'a, T> {
+        GridView {
+            x_axis: self.y_axis,
+            y_axis: self.x_axis,
+            size: IVec2::new(self.size.y, self.size.x),
+            ..*self
+        }
+    }
+
+    pub fn flip_horizontal(&self) -> GridView<'a, T> {
+        GridView {
+            origin: self.origin + self.x_axis * (self.size.x - 1),
+            x_axis: -self.x_axis,
+            ..*self
+        }
+    }
+
+    pub fn flip_vertical(&self) -> GridView<'a, T> {
+        GridView {
+            origin: self.origin + self.y_axis * (self.size.y - 1),
+            y_axis: -self.y_axis,
+            ..*self
+        }
+    }
+
+    pub fn rotate_cw(&self) -> GridView<'a, T> {
+        GridView {
+            origin: self.origin + self.y_axis * (self.size.y - 1),
+            x_axis: -self.y_axis,
+            y_axis: self.x_axis,
+            size: IVec2::new(self.size.y, self.size.x),
+            ..*self
+        }
+    }
+
+    pub fn rotate_ccw(&self) -> GridView<'a, T> {
+        GridView {
+            origin: self.origin + self.x_axis * (self.size.x - 1),
+            x_axis: self.y_axis,
+            y_axis: -self.x_axis,
+            size: IVec2::new(self.size.y, self.size.x),
+            ..*self
+        }
+    }
+
+    pub fn apply(&self, symmetry: Symmetry) -> GridView<'a, T> {
+        match symmetry {
+            Symmetry::Identity => *self,
+            Symmetry::RotateCw => self.rotate_cw(),
+            Symmetry::Rotate180 => self.rotate_cw().rotate_cw(),
+            Symmetry::RotateCcw => self.rotate_ccw(),
+            Symmetry::FlipHorizontal => self.flip_horizontal(),
+            Symmetry::FlipVertical => self.flip_vertical(),
+            Symmetry::Transpose => self.transpose(),
+            Symmetry::AntiTranspose => self.rotate_cw().flip_vertical(),
+        }
+    }
+
+    /// Restrict the view to the rectangle from `min` (inclusive) to `max`
+    /// (exclusive), clamped to the current bounds.
+    pub fn crop(&self, min: IVec2, max: IVec2) -> GridView<'a, T> {
+        let min = min.clamp(IVec2::ZERO, self.size);
+        let max = max.clamp(min, self.size);
+        GridView {
+            origin: self.origin + self.x_axis * min.x + self.y_axis * min.y,
+            size: max - min,
+            ..*self
+        }
+    }
+
+    pub fn to_grid(&self) -> Grid<T> where T: Clone {
+        Grid::from_fn(self.width(), self.height(), |p| self.get_ref(p).unwrap().clone())
+    }
+}
+
+impl<'a, T: GridTile> Debug for GridView<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                f.write_char(self.get_ref(IVec2::new(x, y)).unwrap().to_char())?;
+            }
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use glam::IVec2;
+use rayon::prelude::*;
+
+use crate::ascii_grid::{Direction, Grid};
+
+/// The surroundings of a cell passed to an automaton rule.
+pub struct Neighbours<T> {
+    position: IVec2,
+    cells: [Option<T>; 8],
+}
+
+impl<T: Copy> Neighbours<T> {
+    fn new(grid: &Grid<T>, position: IVec2) -> Neighbours<T> {
+        let mut cells = [None; 8];
+        for d in Direction::all() {
+            cells[d as usize] = grid.get(position + d.delta());
+        }
+
+        Neighbours {
+            position,
+            cells,
+        }
+    }
+
+    /// The position of the cell being updated.
+    pub fn position(&self) -> IVec2 {
+        self.position
+    }
+
+    /// The neighbour in `direction`, or `None` if it is outside the grid.
+    pub fn get(&self, direction: Direction) -> Option<T> {
+        self.cells[direction as usize]
+    }
+
+    /// Every in-bounds neighbour, in [`Direction::all`] order.
+    pub fn iter(&self) -> impl Iterator<Item = (Direction, T)> + '_ {
+        Direction::all().filter_map(|d| self.get(d).map(|t| (d, t)))
+    }
+
+    /// The number of in-bounds neighbours for which `predicate` holds.
+    pub fn count(&self, mut predicate: impl FnMut(T) -> bool) -> usize {
+        self.cells.iter().flatten().filter(|&&t| predicate(t)).count()
+    }
+
+    /// Like [`Neighbours::count`], but only looking at orthogonal neighbours.
+    pub fn count_cardinal(&self, mut predicate: impl FnMut(T) -> bool) -> usize {
+        Direction::cardinal()
+            .filter_map(|d| self.get(d))
+            .filter(|&t| predicate(t))
+            .count()
+    }
+}
+
+/// A double-buffered cellular automaton which updates every cell of a grid
+/// at once, in parallel by rows.
+pub struct Automaton<T> {
+    current: Grid<T>,
+    next: Grid<T>,
+    generation: usize,
+}
+
+impl<T: Copy + PartialEq + Send + Sync> Automaton<T> {
+    pub fn new(grid: Grid<T>) -> Automaton<T> {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    /// The number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Apply `rule` to every cell, returning whether any cell changed.
+    pub fn step(&mut self, rule: impl Fn(T, &Neighbours<T>) -> T + Sync) -> bool {
+        let current = &self.current;
+        let width = current.width().max(1);
+
+        let changed = self.next
+            .par_chunks_exact_mut(width)
+            .zip(current.par_chunks_exact(width))
+            .enumerate()
+            .map(|(y, (next_row, row))| {
+                let mut changed = false;
+                for (x, (out, &cell)) in next_row.iter_mut().zip(row).enumerate() {
+                    let neighbours = Neighbours::new(current, IVec2::new(x as i32, y as i32));
+                    *out = rule(cell, &neighbours);
+                    changed |= *out != cell;
+                }
+                changed
+            })
+            .reduce(|| false, |a, b| a || b);
+
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Step `steps` times, returning whether the last step changed anything.
+    pub fn run(&mut self, steps: usize, rule: impl Fn(T, &Neighbours<T>) -> T + Sync) -> bool {
+        let mut changed = false;
+        for _ in 0..steps {
+            changed = self.step(&rule);
+        }
+        changed
+    }
+
+    /// Step until nothing changes, returning the number of steps which did
+    /// change something. Never returns if the automaton oscillates; combine
+    /// with [`crate::cycle`] for those.
+    pub fn run_until_stable(&mut self, rule: impl Fn(T, &Neighbours<T>) -> T + Sync) -> usize {
+        let mut steps = 0;
+        while self.step(&rule) {
+            steps += 1;
+        }
+        steps
+    }
+}
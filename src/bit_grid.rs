@@ -0,0 +1,304 @@
+use std::fmt::{Debug, Formatter, Write};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use glam::IVec2;
+
+use crate::ascii_grid::{AsciiGrid, Grid};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A grid of booleans packed one bit per cell, with each row starting on a
+/// fresh word so that rows can be shifted and combined a word at a time.
+///
+/// Bits past the end of each row are always kept clear.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    width: usize,
+    height: usize,
+    row_words: usize,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let row_words = width.div_ceil(WORD_BITS);
+        BitGrid {
+            words: vec![0; row_words * height],
+            width,
+            height,
+            row_words,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> IVec2 {
+        IVec2::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn contains(&self, at: IVec2) -> bool {
+        at.x >= 0 && (at.x as usize) < self.width && at.y >= 0 && (at.y as usize) < self.height
+    }
+
+    fn locate(&self, at: IVec2) -> Option<(usize, u64)> {
+        if self.contains(at) {
+            let (x, y) = (at.x as usize, at.y as usize);
+            Some((y * self.row_words + x / WORD_BITS, 1 << (x % WORD_BITS)))
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, at: IVec2) -> Option<bool> {
+        self.locate(at).map(|(i, mask)| self.words[i] & mask != 0)
+    }
+
+    /// Set the cell at `at`, returning the previous value, or `None` if `at`
+    /// is outside the grid.
+    pub fn set(&mut self, at: IVec2, value: bool) -> Option<bool> {
+        let (i, mask) = self.locate(at)?;
+        let previous = self.words[i] & mask != 0;
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+        Some(previous)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// The number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    /// Every set cell, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> + '_ {
+        self.words.iter()
+            .enumerate()
+            .flat_map(move |(i, &word)| {
+                let y = i / self.row_words;
+                let base = (i % self.row_words) * WORD_BITS;
+                BitIter(word).map(move |bit| IVec2::new((base + bit) as i32, y as i32))
+            })
+    }
+
+    fn end_mask(&self) -> u64 {
+        match self.width % WORD_BITS {
+            0 => !0,
+            n => (1 << n) - 1,
+        }
+    }
+
+    fn mask_rows(&mut self) {
+        if self.row_words == 0 {
+            return;
+        }
+
+        let mask = self.end_mask();
+        for row in self.words.chunks_exact_mut(self.row_words) {
+            *row.last_mut().unwrap() &= mask;
+        }
+    }
+
+    /// Combine `other` into `self` a word at a time, without allocating.
+    fn combine(&mut self, other: &BitGrid, f: impl Fn(&mut u64, u64)) {
+        assert_eq!(self.size(), other.size(), "bit grid sizes differ");
+        for (a, &b) in self.words.iter_mut().zip(&other.words) {
+            f(a, b);
+        }
+    }
+
+    pub fn union(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result |= other;
+        result
+    }
+
+    pub fn intersection(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result &= other;
+        result
+    }
+
+    pub fn difference(&self, other: &BitGrid) -> BitGrid {
+        let mut result = self.clone();
+        result.remove_all(other);
+        result
+    }
+
+    /// Clear every cell which is set in `other`, in place.
+    pub fn remove_all(&mut self, other: &BitGrid) {
+        self.combine(other, |a, b| *a &= !b);
+    }
+
+    /// Move every cell by `delta`, so that `result[p] == self[p - delta]`.
+    /// Cells shifted off the edge are dropped and vacated cells are cleared.
+    pub fn shifted(&self, delta: IVec2) -> BitGrid {
+        let mut result = BitGrid::new(self.width, self.height);
+        if delta.x.unsigned_abs() as usize >= self.width || delta.y.unsigned_abs() as usize >= self.height {
+            return result;
+        }
+
+        for y in 0..self.height {
+            let source_y = y as i64 - delta.y as i64;
+            if source_y < 0 || source_y >= self.height as i64 {
+                continue;
+            }
+
+            let source = self.row(source_y as usize);
+            let target = &mut result.words[y * self.row_words..(y + 1) * self.row_words];
+            shift_row(source, target, delta.x);
+        }
+
+        result.mask_rows();
+        result
+    }
+
+    /// Convert to a byte grid, drawing set cells as `on` and clear cells as
+    /// `off`.
+    pub fn to_ascii(&self, on: u8, off: u8) -> AsciiGrid {
+        Grid::from_fn(self.width, self.height, |p| if self.get(p).unwrap() { on } else { off })
+    }
+}
+
+/// Shift the bits of `source` towards higher indices by `shift` (or lower
+/// indices if negative), writing into `target`.
+fn shift_row(source: &[u64], target: &mut [u64], shift: i32) {
+    let n = source.len() as i64;
+    let words = shift.unsigned_abs() as i64 / WORD_BITS as i64;
+    let bits = shift.unsigned_abs() % WORD_BITS as u32;
+
+    for (i, out) in target.iter_mut().enumerate() {
+        let i = i as i64;
+        let word = |j: i64| if (0..n).contains(&j) { source[j as usize] } else { 0 };
+
+        *out = if shift >= 0 {
+            let hi = word(i - words);
+            let lo = word(i - words - 1);
+            if bits == 0 { hi } else { (hi << bits) | (lo >> (WORD_BITS as u32 - bits)) }
+        } else {
+            let lo = word(i + words);
+            let hi = word(i + words + 1);
+            if bits == 0 { lo } else { (lo >> bits) | (hi << (WORD_BITS as u32 - bits)) }
+        };
+    }
+}
+
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Pack the cells for which `predicate` returns true into a [`BitGrid`].
+    pub fn to_bitgrid(&self, mut predicate: impl FnMut(&T) -> bool) -> BitGrid {
+        let mut bits = BitGrid::new(self.width(), self.height());
+        for (y, row) in self.rows().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                if predicate(t) {
+                    bits.words[y * bits.row_words + x / WORD_BITS] |= 1 << (x % WORD_BITS);
+                }
+            }
+        }
+        bits
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |a, b| *a |= b);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |a, b| *a &= b);
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |a, b| *a ^= b);
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result ^= rhs;
+        result
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mut result = self.clone();
+        for word in &mut result.words {
+            *word = !*word;
+        }
+        result.mask_rows();
+        result
+    }
+}
+
+impl Debug for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                f.write_char(if self.get(IVec2::new(x, y)).unwrap() { '#' } else { '.' })?;
+            }
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use glam::{IVec2, IVec3};
+
+/// A point whose bounding box can be tracked component-wise.
+pub(crate) trait Corner: Copy {
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+
+    /// Whether any component is equal to the same component of `other`.
+    fn touches(self, other: Self) -> bool;
+}
+
+impl Corner for IVec2 {
+    fn min(self, other: Self) -> Self {
+        IVec2::min(self, other)
+    }
+
+    fn max(self, other: Self) -> Self {
+        IVec2::max(self, other)
+    }
+
+    fn touches(self, other: Self) -> bool {
+        self.cmpeq(other).any()
+    }
+}
+
+impl Corner for IVec3 {
+    fn min(self, other: Self) -> Self {
+        IVec3::min(self, other)
+    }
+
+    fn max(self, other: Self) -> Self {
+        IVec3::max(self, other)
+    }
+
+    fn touches(self, other: Self) -> bool {
+        self.cmpeq(other).any()
+    }
+}
+
+/// The inclusive bounds after adding `at`.
+pub(crate) fn grow<P: Corner>(bounds: Option<(P, P)>, at: P) -> (P, P) {
+    match bounds {
+        Some((min, max)) => (min.min(at), max.max(at)),
+        None => (at, at),
+    }
+}
+
+/// The inclusive bounds after removing `at`, recomputed from `remaining` only
+/// if `at` was on the edge.
+pub(crate) fn shrink<P: Corner>(
+    bounds: Option<(P, P)>,
+    at: P,
+    remaining: impl IntoIterator<Item = P>,
+) -> Option<(P, P)> {
+    let (min, max) = bounds?;
+    if !at.touches(min) && !at.touches(max) {
+        return bounds;
+    }
+
+    remaining.into_iter().fold(None, |acc, p| Some(grow(acc, p)))
+}
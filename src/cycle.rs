@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A cycle in a sequence of states `x0, f(x0), f(f(x0)), ...`: the sequence
+/// repeats with period `length` from step `start` onwards.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cycle {
+    start: usize,
+    length: usize,
+}
+
+impl Cycle {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The earliest step with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// Compute the state at step `n` by only simulating up to
+    /// [`Cycle::reduce`]`(n)` steps.
+    pub fn nth_state<S: Clone>(&self, initial: &S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        let mut state = initial.clone();
+        for _ in 0..self.reduce(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// Floyd's tortoise and hare algorithm. Uses constant memory, but never
+/// returns if the sequence does not cycle.
+pub fn floyd<S: PartialEq + Clone>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    let mut tortoise = initial.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle {
+        start,
+        length,
+    }
+}
+
+/// Brent's algorithm. Uses constant memory and typically fewer steps than
+/// [`floyd`], but never returns if the sequence does not cycle.
+pub fn brent<S: PartialEq + Clone>(initial: &S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle {
+        start,
+        length,
+    }
+}
+
+/// Find a cycle by remembering every state seen. Returns the cycle along with
+/// the states from step 0 up to the end of the first repetition.
+pub fn detect<S: Hash + Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return (cycle, states);
+        }
+
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps, remembering every state seen so that once a
+/// cycle is found the answer can be read back without further simulation.
+pub fn nth_state<S: Hash + Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut seen = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    while states.len() < n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: states.len() - start,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
@@ -0,0 +1,164 @@
+use glam::{I64Vec2, IVec2};
+
+use crate::ascii_grid::Direction;
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn edges<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> impl Iterator<Item = (I64Vec2, I64Vec2)> + '_ {
+    let points = vertices.iter().map(|&p| p.into());
+    points.clone().zip(points.cycle().skip(1))
+}
+
+/// The z component of the cross product, widened so that 64-bit
+/// coordinates can't overflow.
+fn cross(a: I64Vec2, b: I64Vec2) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
+
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// Twice the signed area of the polygon, by the shoelace formula. The polygon
+/// is implicitly closed. The sign is positive for vertices which run
+/// clockwise on screen (with y pointing down).
+pub fn signed_double_area<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i128 {
+    edges(vertices)
+        .map(|(a, b)| cross(a, b))
+        .sum()
+}
+
+/// Twice the area of the polygon. Lattice polygons can have half-integer
+/// areas, so this is the exact integer form.
+pub fn double_area<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i128 {
+    signed_double_area(vertices).abs()
+}
+
+/// The total length of the polygon's edges, measured with the Manhattan
+/// metric. This is the exact perimeter for axis-aligned polygons.
+pub fn perimeter<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| (b.x - a.x).abs() + (b.y - a.y).abs())
+        .sum()
+}
+
+/// The number of lattice points lying on the polygon's edges.
+pub fn boundary_points<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i64 {
+    edges(vertices)
+        .map(|(a, b)| gcd(b.x - a.x, b.y - a.y))
+        .sum()
+}
+
+/// The number of lattice points strictly inside the polygon, by Pick's
+/// theorem. The polygon must be simple. Degenerate polygons, with fewer than
+/// three vertices or no area, have no interior.
+pub fn interior_points<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i128 {
+    let area = double_area(vertices);
+    if vertices.len() < 3 || area == 0 {
+        return 0;
+    }
+
+    (area - boundary_points(vertices) as i128 + 2) / 2
+}
+
+/// The number of lattice points inside or on the polygon, which must be
+/// simple. When the vertices trace the centres of grid cells, this is the
+/// number of cells enclosed by the loop, including the loop itself.
+///
+/// A degenerate polygon with no area is just its outline: a lone vertex
+/// encloses one point, and a segment the points along it.
+pub fn enclosed_points<P: Copy + Into<I64Vec2>>(vertices: &[P]) -> i128 {
+    if vertices.is_empty() {
+        return 0;
+    }
+
+    let boundary = boundary_points(vertices) as i128;
+    if double_area(vertices) == 0 {
+        // The closing walk covers the outline twice.
+        return boundary / 2 + 1;
+    }
+
+    interior_points(vertices) + boundary
+}
+
+/// Classify `point` against the polygon using the crossing-number rule.
+pub fn point_in_polygon<P: Copy + Into<I64Vec2>>(vertices: &[P], point: P) -> Containment {
+    let p = point.into();
+    let mut inside = false;
+
+    for (a, b) in edges(vertices) {
+        let cross = cross(b - a, p - a);
+        if cross == 0 && p.cmpge(a.min(b)).all() && p.cmple(a.max(b)).all() {
+            return Containment::Boundary;
+        }
+
+        if (a.y > p.y) != (b.y > p.y) {
+            // The edge straddles the horizontal line through p; count it if it
+            // crosses to the right of p.
+            let crosses = if b.y > a.y { cross > 0 } else { cross < 0 };
+            if crosses {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Follow a series of moves from `start`, returning the vertex at the end of
+/// each move preceded by `start`. If the moves return to `start`, the
+/// repeated final vertex is dropped.
+///
+/// Positions are accumulated in 64 bits, so long moves such as those in a
+/// dig plan don't overflow.
+pub fn vertices_from_moves(start: I64Vec2, moves: impl IntoIterator<Item = (Direction, i64)>) -> Vec<I64Vec2> {
+    let mut vertices = vec![start];
+    let mut at = start;
+
+    for (direction, length) in moves {
+        at += direction.delta().as_i64vec2() * length;
+        vertices.push(at);
+    }
+
+    if vertices.len() > 1 && vertices.last() == Some(&start) {
+        vertices.pop();
+    }
+
+    vertices
+}
+
+/// The number of orthogonal steps between two points: the distance a
+/// 4-connected walk covers on an open grid.
+pub fn manhattan(a: IVec2, b: IVec2) -> i64 {
+    let d = (a.as_i64vec2() - b.as_i64vec2()).abs();
+    d.x + d.y
+}
+
+/// The number of king's moves between two points: the distance an
+/// 8-connected walk covers on an open grid.
+pub fn chebyshev(a: IVec2, b: IVec2) -> i64 {
+    let d = (a.as_i64vec2() - b.as_i64vec2()).abs();
+    d.x.max(d.y)
+}
+
+/// The square of the straight-line distance between two points, which is
+/// exact and orders the same way as the distance itself. This is `i128`
+/// because the square of an `i64` difference can overflow `i64`.
+pub fn euclidean_squared(a: IVec2, b: IVec2) -> i128 {
+    let d = a.as_i64vec2() - b.as_i64vec2();
+    let (dx, dy) = (d.x as i128, d.y as i128);
+    dx * dx + dy * dy
+}
@@ -0,0 +1,238 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use glam::IVec2;
+
+/// A hex grid position in axial coordinates. The implied third cube
+/// coordinate is `s = -q - r`.
+///
+/// Hexes are flat-topped: `q` increases to the south-east and `r` to the
+/// south.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ZERO: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// Build a hex from cube coordinates, which must sum to zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Hex> {
+        (q + r + s == 0).then_some(Hex::new(q, r))
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(self, other: Hex) -> i32 {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    pub fn neighbour(self, direction: HexDirection) -> Hex {
+        self + direction.delta()
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = Hex> {
+        HexDirection::all().map(move |d| self + d.delta())
+    }
+
+    /// Every hex exactly `radius` steps from `self`, walking clockwise from
+    /// the south-west corner.
+    pub fn ring(self, radius: i32) -> Vec<Hex> {
+        if radius <= 0 {
+            return vec![self];
+        }
+
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut hex = self + HexDirection::SouthWest.delta() * radius;
+        for direction in HexDirection::all() {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbour(direction);
+            }
+        }
+
+        ring
+    }
+
+    /// Every hex within `radius` steps of `self`, ordered by distance: `self`
+    /// first, then each ring in turn.
+    pub fn spiral(self, radius: i32) -> Vec<Hex> {
+        let mut spiral = vec![self];
+        for r in 1..=radius {
+            spiral.extend(self.ring(r));
+        }
+        spiral
+    }
+}
+
+impl From<IVec2> for Hex {
+    fn from(value: IVec2) -> Self {
+        Hex::new(value.x, value.y)
+    }
+}
+
+impl From<Hex> for IVec2 {
+    fn from(value: Hex) -> Self {
+        IVec2::new(value.q, value.r)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl SubAssign for Hex {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Self::Output {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// The six neighbours of a flat-topped hex, in clockwise order.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum HexDirection {
+    North = 0,
+    NorthEast = 1,
+    SouthEast = 2,
+    South = 3,
+    SouthWest = 4,
+    NorthWest = 5,
+}
+
+impl HexDirection {
+    fn from_index(index: u8) -> HexDirection {
+        match index % 6 {
+            0 => HexDirection::North,
+            1 => HexDirection::NorthEast,
+            2 => HexDirection::SouthEast,
+            3 => HexDirection::South,
+            4 => HexDirection::SouthWest,
+            5 => HexDirection::NorthWest,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn all() -> impl DoubleEndedIterator<Item = HexDirection> + ExactSizeIterator + Clone {
+        (0..6).map(HexDirection::from_index)
+    }
+
+    pub fn delta(self) -> Hex {
+        match self {
+            HexDirection::North => Hex::new(0, -1),
+            HexDirection::NorthEast => Hex::new(1, -1),
+            HexDirection::SouthEast => Hex::new(1, 0),
+            HexDirection::South => Hex::new(0, 1),
+            HexDirection::SouthWest => Hex::new(-1, 1),
+            HexDirection::NorthWest => Hex::new(-1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> HexDirection {
+        HexDirection::from_index(self as u8 + 3)
+    }
+
+    /// Rotate 60° clockwise.
+    pub fn turn_right(self) -> HexDirection {
+        HexDirection::from_index(self as u8 + 1)
+    }
+
+    /// Rotate 60° anticlockwise.
+    pub fn turn_left(self) -> HexDirection {
+        HexDirection::from_index(self as u8 + 5)
+    }
+
+    fn from_token(token: &str) -> Option<HexDirection> {
+        match token {
+            "n" | "N" => Some(HexDirection::North),
+            "ne" | "NE" => Some(HexDirection::NorthEast),
+            "se" | "SE" => Some(HexDirection::SouthEast),
+            "s" | "S" => Some(HexDirection::South),
+            "sw" | "SW" => Some(HexDirection::SouthWest),
+            "nw" | "NW" => Some(HexDirection::NorthWest),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HexDirection::from_token(s)
+            .ok_or_else(|| anyhow!("invalid hex direction {s:?}"))
+    }
+}
+
+/// Parse a hex path such as `ne,s,nw`. Steps may be separated by commas
+/// and/or whitespace, or run together as in `nesnw`.
+pub fn parse_path(s: &str) -> anyhow::Result<Vec<HexDirection>> {
+    let mut path = Vec::new();
+
+    for part in s.split(|c: char| c == ',' || c.is_whitespace()) {
+        let mut rest = part;
+        while !rest.is_empty() {
+            let step = rest.get(..2)
+                .and_then(HexDirection::from_token)
+                .map(|d| (2, d))
+                .or_else(|| rest.get(..1)
+                    .and_then(HexDirection::from_token)
+                    .map(|d| (1, d)));
+            let Some((len, direction)) = step else {
+                bail!("invalid hex direction at {rest:?}");
+            };
+
+            path.push(direction);
+            rest = &rest[len..];
+        }
+    }
+
+    Ok(path)
+}
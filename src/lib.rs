@@ -5,6 +5,24 @@ pub mod find_numbers;
 
 pub mod ascii_grid;
 
+pub mod bit_grid;
+
+mod bounds;
+
+pub mod search;
+
+pub mod cycle;
+
+pub mod render;
+
+pub mod geometry;
+
+pub mod hex;
+
+pub mod voxel;
+
+pub mod automaton;
+
 pub fn convert_nom_error(e: nom::Err<nom::error::Error<&str>>) -> anyhow::Error {
     e.to_owned().into()
 }
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Write as _};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use glam::IVec2;
+
+use crate::ascii_grid::{Grid, GridTile};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(133, 153, 0);
+    pub const BLUE: Rgb = Rgb(38, 139, 210);
+    pub const YELLOW: Rgb = Rgb(181, 137, 0);
+}
+
+/// A reasonable default colouring for ASCII puzzle input: `.` is black, `#`
+/// is white, and every other byte gets a stable, distinct-ish colour.
+pub fn ascii_palette(b: u8) -> Rgb {
+    match b {
+        b'.' | b' ' => Rgb::BLACK,
+        b'#' => Rgb::WHITE,
+        _ => {
+            let h = (b as u32).wrapping_mul(0x9e3779b1);
+            Rgb(64 + (h >> 24) as u8 % 192, 64 + (h >> 16) as u8 % 192, 64 + (h >> 8) as u8 % 192)
+        }
+    }
+}
+
+type TerminalPalette<'a, T> = Box<dyn Fn(IVec2, &T) -> Option<Rgb> + 'a>;
+
+/// Renders a grid to a terminal using 24-bit ANSI colour escapes.
+///
+/// Cells are given a foreground colour by the palette, and highlighted
+/// positions are drawn with a background colour on top.
+pub struct Terminal<'a, T> {
+    grid: &'a Grid<T>,
+    palette: TerminalPalette<'a, T>,
+    highlights: HashMap<IVec2, Rgb>,
+}
+
+impl<'a, T: GridTile> Terminal<'a, T> {
+    pub fn new(grid: &'a Grid<T>) -> Terminal<'a, T> {
+        Terminal {
+            grid,
+            palette: Box::new(|_, _| None),
+            highlights: HashMap::new(),
+        }
+    }
+
+    /// Colour each cell, or leave it in the default colour by returning
+    /// `None`.
+    pub fn palette(mut self, palette: impl Fn(IVec2, &T) -> Option<Rgb> + 'a) -> Terminal<'a, T> {
+        self.palette = Box::new(palette);
+        self
+    }
+
+    /// Highlight `positions` with a background colour. Later highlights
+    /// replace earlier ones.
+    pub fn highlight(mut self, positions: impl IntoIterator<Item = IVec2>, colour: Rgb) -> Terminal<'a, T> {
+        self.highlights.extend(positions.into_iter().map(|p| (p, colour)));
+        self
+    }
+}
+
+impl<'a, T: GridTile> Display for Terminal<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                let p = IVec2::new(x as i32, y as i32);
+                let fg = (self.palette)(p, tile);
+                let bg = self.highlights.get(&p);
+
+                if let Some(Rgb(r, g, b)) = fg {
+                    write!(f, "\x1b[38;2;{r};{g};{b}m")?;
+                }
+                if let Some(Rgb(r, g, b)) = bg {
+                    write!(f, "\x1b[48;2;{r};{g};{b}m")?;
+                }
+
+                f.write_char(tile.to_char())?;
+
+                if fg.is_some() || bg.is_some() {
+                    f.write_str("\x1b[0m")?;
+                }
+            }
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            #[cfg(feature = "png")]
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+fn rasterise<T>(grid: &Grid<T>, scale: usize, palette: impl Fn(IVec2, &T) -> Rgb) -> (usize, usize, Vec<u8>) {
+    let width = grid.width() * scale;
+    let height = grid.height() * scale;
+    let mut pixels = Vec::with_capacity(width * height * 3);
+
+    for (y, row) in grid.rows().enumerate() {
+        let mut line = Vec::with_capacity(width * 3);
+        for (x, tile) in row.iter().enumerate() {
+            let Rgb(r, g, b) = palette(IVec2::new(x as i32, y as i32), tile);
+            for _ in 0..scale {
+                line.extend_from_slice(&[r, g, b]);
+            }
+        }
+
+        for _ in 0..scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+
+    (width, height, pixels)
+}
+
+/// Write a binary PPM image with each cell drawn as a `scale`×`scale` block.
+pub fn write_ppm<T>(
+    grid: &Grid<T>,
+    scale: usize,
+    palette: impl Fn(IVec2, &T) -> Rgb,
+    mut out: impl Write,
+) -> io::Result<()> {
+    let (width, height, pixels) = rasterise(grid, scale, palette);
+    write!(out, "P6\n{width} {height}\n255\n")?;
+    out.write_all(&pixels)
+}
+
+/// Write a PNG image with each cell drawn as a `scale`×`scale` block.
+#[cfg(feature = "png")]
+pub fn write_png<T>(
+    grid: &Grid<T>,
+    scale: usize,
+    palette: impl Fn(IVec2, &T) -> Rgb,
+    out: impl Write,
+) -> io::Result<()> {
+    let (width, height, pixels) = rasterise(grid, scale, palette);
+    let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&pixels).map_err(io::Error::other)
+}
+
+pub fn write_image<T>(
+    grid: &Grid<T>,
+    format: ImageFormat,
+    scale: usize,
+    palette: impl Fn(IVec2, &T) -> Rgb,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        ImageFormat::Ppm => write_ppm(grid, scale, palette, &mut out)?,
+        #[cfg(feature = "png")]
+        ImageFormat::Png => write_png(grid, scale, palette, &mut out)?,
+    }
+    out.flush()
+}
+
+/// Writes a numbered sequence of images, e.g. `frames/step00000.ppm`,
+/// `frames/step00001.ppm`, ..., for stitching into an animation.
+pub struct FrameWriter {
+    directory: PathBuf,
+    prefix: String,
+    format: ImageFormat,
+    scale: usize,
+    next: usize,
+}
+
+impl FrameWriter {
+    /// Create a frame writer, creating `directory` if it does not exist.
+    pub fn new(directory: impl Into<PathBuf>, prefix: &str, format: ImageFormat, scale: usize) -> io::Result<FrameWriter> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        Ok(FrameWriter {
+            directory,
+            prefix: prefix.to_string(),
+            format,
+            scale,
+            next: 0,
+        })
+    }
+
+    pub fn frames_written(&self) -> usize {
+        self.next
+    }
+
+    /// Write the next frame, returning the path it was written to.
+    pub fn write<T>(&mut self, grid: &Grid<T>, palette: impl Fn(IVec2, &T) -> Rgb) -> io::Result<PathBuf> {
+        let name = format!("{}{:05}.{}", self.prefix, self.next, self.format.extension());
+        let path = self.directory.join(name);
+        write_image(grid, self.format, self.scale, palette, &path)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
@@ -0,0 +1,205 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use glam::IVec2;
+
+use crate::ascii_grid::{Direction, Grid};
+
+/// The outcome of a search: the distance to every settled state, along with
+/// enough information to reconstruct the path to each of them.
+#[derive(Clone, Debug)]
+pub struct SearchResult<S> {
+    distances: HashMap<S, u64>,
+    parents: HashMap<S, S>,
+    goal: Option<S>,
+}
+
+impl<S: Hash + Eq + Clone> SearchResult<S> {
+    fn new() -> SearchResult<S> {
+        SearchResult {
+            distances: HashMap::new(),
+            parents: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn distances(&self) -> &HashMap<S, u64> {
+        &self.distances
+    }
+
+    pub fn distance(&self, state: &S) -> Option<u64> {
+        self.distances.get(state).copied()
+    }
+
+    /// The first goal state reached, if any.
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_distance(&self) -> Option<u64> {
+        self.goal.as_ref().and_then(|g| self.distance(g))
+    }
+
+    /// The path from one of the start states to `state`, inclusive of both
+    /// ends.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal state, if one was reached.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+}
+
+/// Breadth-first search where every step costs 1.
+///
+/// The search stops as soon as a state satisfying `is_goal` is dequeued; pass
+/// `|_| false` to explore everything reachable.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, distance)) = queue.pop_front() {
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        for next in neighbours(&state) {
+            if result.distances.contains_key(&next) {
+                continue;
+            }
+
+            result.distances.insert(next.clone(), distance + 1);
+            result.parents.insert(next.clone(), state.clone());
+            queue.push_back((next, distance + 1));
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm, with `neighbours` yielding each successor state along
+/// with the cost of moving to it.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// A* search. States are settled the first time they are popped and never
+/// re-opened, so for the result to be optimal `heuristic` must be consistent
+/// (monotone): it must never drop by more than the cost of a move. Merely
+/// never overestimating the remaining cost is not enough.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut result = SearchResult::new();
+    let mut best = HashMap::new();
+    let mut pending = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        best.insert(start.clone(), 0);
+        heap.push(Reverse((heuristic(&start), 0, pending.len())));
+        pending.push((start, None));
+    }
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let (state, parent) = pending[index].clone();
+        if result.distances.contains_key(&state) {
+            continue;
+        }
+
+        result.distances.insert(state.clone(), cost);
+        if let Some(parent) = parent {
+            result.parents.insert(state.clone(), parent);
+        }
+
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if result.distances.contains_key(&next) || best.get(&next).is_some_and(|&c| c <= next_cost) {
+                continue;
+            }
+
+            best.insert(next.clone(), next_cost);
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, pending.len())));
+            pending.push((next, Some(state.clone())));
+        }
+    }
+
+    result
+}
+
+/// Build a `neighbours` function for [`bfs`] which steps in each of
+/// `directions`, staying inside `grid` and only entering cells for which
+/// `passable` returns true.
+pub fn grid_neighbours<'a, T>(
+    grid: &'a Grid<T>,
+    directions: &'a [Direction],
+    mut passable: impl FnMut(IVec2, &T) -> bool + 'a,
+) -> impl FnMut(&IVec2) -> Vec<IVec2> + 'a {
+    move |&p| directions.iter()
+        .map(|d| p + d.delta())
+        .filter(|&q| grid.get_ref(q).is_some_and(|t| passable(q, t)))
+        .collect()
+}
+
+/// Build a `neighbours` function for [`dijkstra`] or [`astar`] which steps in
+/// each of `directions`, staying inside `grid`. `cost` gives the cost of
+/// entering a cell, or `None` if it cannot be entered.
+pub fn grid_weighted_neighbours<'a, T>(
+    grid: &'a Grid<T>,
+    directions: &'a [Direction],
+    mut cost: impl FnMut(IVec2, &T) -> Option<u64> + 'a,
+) -> impl FnMut(&IVec2) -> Vec<(IVec2, u64)> + 'a {
+    move |&p| directions.iter()
+        .map(|d| p + d.delta())
+        .filter_map(|q| grid.get_ref(q).and_then(|t| cost(q, t)).map(|c| (q, c)))
+        .collect()
+}
@@ -0,0 +1,329 @@
+use std::collections::hash_map;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use glam::{IVec2, IVec3};
+
+use crate::ascii_grid::{AsciiGrid, Grid};
+use crate::bounds;
+
+/// The six face-adjacent offsets.
+pub const FACE_DELTAS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+/// The face-adjacent neighbours of `p`.
+pub fn neighbours6(p: IVec3) -> impl Iterator<Item = IVec3> {
+    FACE_DELTAS.into_iter().map(move |d| p + d)
+}
+
+/// The face-, edge- and corner-adjacent neighbours of `p`.
+pub fn neighbours26(p: IVec3) -> impl Iterator<Item = IVec3> {
+    (-1..=1)
+        .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| IVec3::new(x, y, z))))
+        .filter(|&d| d != IVec3::ZERO)
+        .map(move |d| p + d)
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// Map a position within a slice perpendicular to this axis back to 3D.
+    /// Slices across `X` are laid out as (y, z), across `Y` as (x, z) and
+    /// across `Z` as (x, y).
+    fn unslice(self, p: IVec2, index: i32) -> IVec3 {
+        match self {
+            Axis::X => IVec3::new(index, p.x, p.y),
+            Axis::Y => IVec3::new(p.x, index, p.y),
+            Axis::Z => IVec3::new(p.x, p.y, index),
+        }
+    }
+
+    fn flatten(self, p: IVec3) -> IVec2 {
+        match self {
+            Axis::X => IVec2::new(p.y, p.z),
+            Axis::Y => IVec2::new(p.x, p.z),
+            Axis::Z => IVec2::new(p.x, p.y),
+        }
+    }
+}
+
+/// A dense 3D grid covering a fixed box.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct VoxelGrid<T> {
+    contents: Vec<T>,
+    min: IVec3,
+    size: IVec3,
+}
+
+impl<T> VoxelGrid<T> {
+    /// Create a grid covering `size` cells starting at `min`.
+    pub fn new(min: IVec3, size: IVec3, fill: T) -> VoxelGrid<T> where T: Clone {
+        let size = size.max(IVec3::ZERO);
+        VoxelGrid {
+            contents: vec![fill; (size.x * size.y * size.z) as usize],
+            min,
+            size,
+        }
+    }
+
+    pub fn min(&self) -> IVec3 {
+        self.min
+    }
+
+    /// The inclusive maximum corner.
+    pub fn max(&self) -> IVec3 {
+        self.min + self.size - IVec3::ONE
+    }
+
+    pub fn size(&self) -> IVec3 {
+        self.size
+    }
+
+    pub fn contains(&self, at: IVec3) -> bool {
+        let p = at - self.min;
+        p.cmpge(IVec3::ZERO).all() && p.cmplt(self.size).all()
+    }
+
+    fn to_offset(&self, at: IVec3) -> Option<usize> {
+        if self.contains(at) {
+            let p = at - self.min;
+            Some((p.x + self.size.x * (p.y + self.size.y * p.z)) as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, at: IVec3) -> Option<T> where T: Copy {
+        self.get_ref(at).copied()
+    }
+
+    pub fn get_ref(&self, at: IVec3) -> Option<&T> {
+        self.to_offset(at).map(|i| &self.contents[i])
+    }
+
+    pub fn get_mut(&mut self, at: IVec3) -> Option<&mut T> {
+        self.to_offset(at).map(|i| &mut self.contents[i])
+    }
+
+    pub fn set(&mut self, at: IVec3, value: T) -> Option<T> {
+        self.get_mut(at).map(|v| std::mem::replace(v, value))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = IVec3> {
+        let (min, size) = (self.min, self.size);
+        (0..size.z)
+            .flat_map(move |z| (0..size.y).flat_map(move |y| (0..size.x).map(move |x| IVec3::new(x, y, z))))
+            .map(move |p| min + p)
+    }
+
+    /// The in-bounds face-adjacent neighbours of `at`.
+    pub fn neighbours6(&self, at: IVec3) -> impl Iterator<Item = IVec3> + '_ {
+        neighbours6(at).filter(|&p| self.contains(p))
+    }
+
+    /// The in-bounds neighbours of `at`, including diagonals.
+    pub fn neighbours26(&self, at: IVec3) -> impl Iterator<Item = IVec3> + '_ {
+        neighbours26(at).filter(|&p| self.contains(p))
+    }
+
+    /// The sparse grid of cells for which `include` returns true.
+    pub fn to_sparse(&self, mut include: impl FnMut(&T) -> bool) -> SparseVoxelGrid<T> where T: Clone {
+        self.positions()
+            .zip(self.contents.iter())
+            .filter(|(_, t)| include(t))
+            .map(|(p, t)| (p, t.clone()))
+            .collect()
+    }
+
+    /// Take the 2D slice perpendicular to `axis` at `index`, drawing each cell
+    /// with `render`, or `.` if `index` lies outside the grid. See [`Axis`]
+    /// for the layout.
+    pub fn slice(&self, axis: Axis, index: i32, mut render: impl FnMut(&T) -> u8) -> AsciiGrid {
+        let min = axis.flatten(self.min);
+        let size = axis.flatten(self.size);
+        Grid::from_fn(size.x as usize, size.y as usize, |p| {
+            self.get_ref(axis.unslice(min + p, index)).map_or(b'.', &mut render)
+        })
+    }
+}
+
+/// An unbounded 3D grid which only stores occupied cells, tracking their
+/// bounding box.
+#[derive(Clone)]
+pub struct SparseVoxelGrid<T> {
+    cells: HashMap<IVec3, T>,
+    bounds: Option<(IVec3, IVec3)>,
+}
+
+impl<T> Default for SparseVoxelGrid<T> {
+    fn default() -> Self {
+        SparseVoxelGrid::new()
+    }
+}
+
+impl<T> SparseVoxelGrid<T> {
+    pub fn new() -> SparseVoxelGrid<T> {
+        SparseVoxelGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The inclusive minimum and maximum corners of the occupied cells.
+    pub fn bounds(&self) -> Option<(IVec3, IVec3)> {
+        self.bounds
+    }
+
+    pub fn contains(&self, at: IVec3) -> bool {
+        self.cells.contains_key(&at)
+    }
+
+    pub fn get(&self, at: IVec3) -> Option<T> where T: Copy {
+        self.cells.get(&at).copied()
+    }
+
+    pub fn get_ref(&self, at: IVec3) -> Option<&T> {
+        self.cells.get(&at)
+    }
+
+    pub fn get_mut(&mut self, at: IVec3) -> Option<&mut T> {
+        self.cells.get_mut(&at)
+    }
+
+    pub fn set(&mut self, at: IVec3, value: T) -> Option<T> {
+        self.bounds = Some(bounds::grow(self.bounds, at));
+        self.cells.insert(at, value)
+    }
+
+    pub fn remove(&mut self, at: IVec3) -> Option<T> {
+        let value = self.cells.remove(&at)?;
+
+        self.bounds = bounds::shrink(self.bounds, at, self.cells.keys().copied());
+        Some(value)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, IVec3, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> hash_map::Keys<'_, IVec3, T> {
+        self.cells.keys()
+    }
+
+    /// The number of faces of occupied cells which do not touch another
+    /// occupied cell.
+    pub fn surface_area(&self) -> usize {
+        self.cells.keys()
+            .flat_map(|&p| neighbours6(p))
+            .filter(|p| !self.cells.contains_key(p))
+            .count()
+    }
+
+    /// Every empty cell reachable from outside the occupied cells, within the
+    /// bounding box grown by one in every direction.
+    pub fn exterior(&self) -> HashSet<IVec3> {
+        let Some((min, max)) = self.bounds else {
+            return HashSet::new();
+        };
+
+        let (min, max) = (min - IVec3::ONE, max + IVec3::ONE);
+        let mut exterior = HashSet::from([min]);
+        let mut queue = VecDeque::from([min]);
+
+        while let Some(p) = queue.pop_front() {
+            for q in neighbours6(p) {
+                if q.cmplt(min).any() || q.cmpgt(max).any() || self.cells.contains_key(&q) {
+                    continue;
+                }
+
+                if exterior.insert(q) {
+                    queue.push_back(q);
+                }
+            }
+        }
+
+        exterior
+    }
+
+    /// Like [`SparseVoxelGrid::surface_area`], but only counting faces which
+    /// can be reached from outside, ignoring internal air pockets.
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.cells.keys()
+            .flat_map(|&p| neighbours6(p))
+            .filter(|p| exterior.contains(p))
+            .count()
+    }
+
+    /// Convert into a dense grid covering [`SparseVoxelGrid::bounds`],
+    /// filling empty cells with `fill`.
+    pub fn to_dense(&self, fill: T) -> VoxelGrid<T> where T: Clone {
+        let Some((min, max)) = self.bounds else {
+            return VoxelGrid::new(IVec3::ZERO, IVec3::ZERO, fill);
+        };
+
+        let mut grid = VoxelGrid::new(min, max - min + IVec3::ONE, fill);
+        for (&p, t) in &self.cells {
+            grid.set(p, t.clone());
+        }
+        grid
+    }
+
+    /// Take the 2D slice perpendicular to `axis` at `index` across the
+    /// bounding box, drawing occupied cells with `render` and empty ones as
+    /// `.`. See [`Axis`] for the layout.
+    pub fn slice(&self, axis: Axis, index: i32, mut render: impl FnMut(&T) -> u8) -> AsciiGrid {
+        let Some((min, max)) = self.bounds else {
+            return Grid::new(0, 0, b'.');
+        };
+
+        let size = axis.flatten(max - min + IVec3::ONE);
+        let min = axis.flatten(min);
+        Grid::from_fn(size.x as usize, size.y as usize, |p| {
+            self.cells.get(&axis.unslice(min + p, index)).map_or(b'.', &mut render)
+        })
+    }
+}
+
+impl<T> FromIterator<(IVec3, T)> for SparseVoxelGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (IVec3, T)>>(iter: I) -> Self {
+        let mut grid = SparseVoxelGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(IVec3, T)> for SparseVoxelGrid<T> {
+    fn extend<I: IntoIterator<Item = (IVec3, T)>>(&mut self, iter: I) {
+        for (p, t) in iter {
+            self.set(p, t);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SparseVoxelGrid<T> {
+    type Item = (&'a IVec3, &'a T);
+    type IntoIter = hash_map::Iter<'a, IVec3, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.iter()
+    }
+}